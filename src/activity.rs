@@ -1,16 +1,27 @@
 use serde_derive::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use colored::Colorize;
 use crate::COLORS;
+use crate::predict::Prediction;
 use crate::settings::Settings;
 
-#[derive(Serialize, Deserialize, Debug, Clone, Eq, Hash)]
+#[derive(Serialize, Deserialize, Debug, Clone, Eq)]
 pub struct Activity {
     pub name: String,
     pub productive: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub comment: Option<String>
+    pub comment: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Key to select the activity with, instead of a derived one. Only read
+    /// from the config, it is not written into the data files.
+    #[serde(default, skip_serializing)]
+    pub shortcut: Option<char>,
+    /// Recorded from a routine of the config.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub routine: bool,
 }
 
 impl PartialEq for Activity {
@@ -19,53 +30,205 @@ impl PartialEq for Activity {
     }
 }
 
+impl Hash for Activity {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+    }
+}
+
 impl Activity {
     pub fn get_by_name(actis: &[Activity], name: &str) -> Option<Self> {
         actis.iter().find(|o| o.name == name).cloned()
     }
 
-    pub fn prompt(settings: &Settings) -> Option<&Activity> {
+    /// Find the single activity matching `text`, see `matches`.
+    pub fn find(settings: &Settings, text: &str) -> Option<Self> {
+        match Activity::matches(settings, text).as_slice() {
+            [activity] => Some((*activity).clone()),
+            _ => None,
+        }
+    }
+
+    /// The activities matching `text`, trying in this order and stopping at
+    /// the first that matches any:
+    /// - the index in the config, like `12`
+    /// - the full name, ignoring case
+    /// - the shortcut, then the shortcut ignoring case
+    /// - the start of the name or the initials of its words, like `dw` for
+    ///   `Deep Work` (at least two characters)
+    /// - the characters of `text` in the same order anywhere in the name,
+    ///   like `wrk` (at least two characters)
+    pub fn matches<'s>(settings: &'s Settings, text: &str) -> Vec<&'s Activity> {
+        let exact = text.trim();
+        let text = exact.to_lowercase();
+        if text.is_empty() {
+            return vec![];
+        }
+        if let Ok(index) = text.parse::<usize>() {
+            return settings.activities.get(index).into_iter().collect();
+        }
         let shortcuts = settings.get_shortcuts();
-        settings.activities.iter().enumerate().for_each(|(i, o)| {
-            let mut name = o.to_string();
-            if let Some(chr) = &shortcuts[i] {
-                name = name.replacen(*chr, &format!("[{}]", chr), 1);
+        let long = text.chars().count() > 1;
+        let matches = |tier: usize, i: usize, activity: &Activity| {
+            let name = activity.name.to_lowercase();
+            match tier {
+                0 => name == text,
+                1 => !long && shortcuts[i].is_some_and(|c| c.to_string() == exact),
+                2 => !long && shortcuts[i].is_some_and(|c| c.to_lowercase().eq(text.chars())),
+                3 => long && (name.starts_with(&text) || initials(&name).starts_with(&text)),
+                _ => long && is_subsequence(&text, &name),
             }
-            println!("\t{}: {}", i, name);
-        });
-        let input = crate::get_input::<String>()?.trim().chars().next()?;
-        let result = if input.is_numeric() {
-            input.to_digit(10)
-                .map(|number| number as usize)
-                .filter(|number| *number < settings.activities.len())
-                .map(|number| &settings.activities[number])
-        } else if input.is_alphabetic() {
-            shortcuts
-                .iter()
-                .position(|s| s.is_some() && s.unwrap() == input)
-                .map(|i| &settings.activities[i])
-        } else {
-            None
         };
-        if let Some(choice) = result {
-            println!("~> {}", choice);
+        (0..5)
+            .map(|tier| settings.activities.iter().enumerate().filter(|(i, a)| matches(tier, *i, a)).map(|(_, a)| a).collect::<Vec<_>>())
+            .find(|found| !found.is_empty())
+            .unwrap_or_default()
+    }
+
+    /// Tags, routine mark and comment as shown after the name.
+    fn details(&self) -> String {
+        let mut details = String::new();
+        for tag in &self.tags {
+            details.push_str(&format!(" #{}", tag));
+        }
+        if self.routine {
+            details.push_str(" ↻");
+        }
+        if let Some(comment) = &self.comment {
+            details.push_str(&format!(" - {}", comment));
+        }
+        details
+    }
+
+    /// The name with its shortcut in brackets, like `[W]ork` or `Work [x]`.
+    fn with_shortcut(&self, shortcut: Option<char>) -> String {
+        let name = match shortcut {
+            None => self.name.clone(),
+            Some(shortcut) => match self.name.char_indices().find(|(_, c)| *c == shortcut) {
+                Some((i, c)) => format!("{}[{}]{}", &self.name[..i], c, &self.name[i + c.len_utf8()..]),
+                None => format!("{} [{}]", self.name, shortcut),
+            },
+        };
+        format!("{}{}", name.color(self.color()), self.details())
+    }
+
+    /// Ask for an activity by its index, name, shortcut or an abbreviation,
+    /// and ask again if that matches several. The activities are listed in
+    /// the order of the prediction, and the likely one is taken on Enter.
+    pub fn prompt<'s>(settings: &'s Settings, prediction: &Prediction) -> Option<&'s Activity> {
+        let shortcuts = settings.get_shortcuts();
+        for i in &prediction.order {
+            println!("\t{}: {}", i, settings.activities[*i].with_shortcut(shortcuts[*i]));
+        }
+        let likely = prediction.likely.map(|i| &settings.activities[i]);
+        if let Some(likely) = likely {
+            println!("(Leave {} for {}.)", "empty".bright_blue(), likely.name.color(likely.color()));
+        }
+        let names = prediction.order.iter().map(|i| settings.activities[*i].name.clone()).collect::<Vec<_>>();
+        loop {
+            let line = crate::get_input_completing::<String>(names.clone())?;
+            let found = match likely {
+                Some(likely) if line.trim().is_empty() => vec![likely],
+                _ => Activity::matches(settings, &line),
+            };
+            match found.as_slice() {
+                [] => return None,
+                [choice] => {
+                    println!("~> {}", choice);
+                    return Some(choice);
+                },
+                candidates => {
+                    println!("'{}' could be:", line.trim());
+                    for candidate in candidates {
+                        let i = settings.activities.iter().position(|a| a == *candidate).unwrap();
+                        println!("\t{}: {}", i, candidate.with_shortcut(shortcuts[i]));
+                    }
+                },
+            }
         }
-        result
     }
 
     pub fn color(&self) -> &'static str {
         // maybe cache this...
         let color_idx = (self.name.chars().map(|c| c as usize).sum::<usize>() + self.name.len()) % COLORS.len();
-        &COLORS[color_idx]
+        COLORS[color_idx]
+    }
+
+    /// CSS equivalent of `color`, for reports and charts.
+    pub fn css_color(&self) -> &'static str {
+        match self.color() {
+            "red" => "#cd3131",
+            "green" => "#0dbc79",
+            "yellow" => "#e5e510",
+            "blue" => "#2472c8",
+            "magenta" => "#bc3fbc",
+            "cyan" => "#11a8cd",
+            "white" => "#e5e5e5",
+            _ => "#808080",
+        }
     }
 }
 
+/// The first letters of the words of `name`.
+fn initials(name: &str) -> String {
+    name.split_whitespace().filter_map(|w| w.chars().next()).collect()
+}
+
+/// Whether the characters of `needle` appear in `haystack` in the same order.
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars();
+    needle.chars().all(|c| haystack.any(|h| h == c))
+}
+
 impl Display for Activity {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        f.write_str(&*format!("{}", self.name.color(self.color())))?;
-        if let Some(comment) = &self.comment {
-            f.write_str(&*format!(" - {}", comment))?;
+        f.write_str(&format!("{}{}", self.name.color(self.color()), self.details()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> Settings {
+        let mut settings = Settings::with_activities(&["Work", "Deep Work", "Email", "Walk"]);
+        settings.activities[2].shortcut = Some('m');
+        settings.activities[3].shortcut = Some('M');
+        settings
+    }
+
+    fn names(settings: &Settings, text: &str) -> Vec<String> {
+        Activity::matches(settings, text).iter().map(|a| a.name.clone()).collect()
+    }
+
+    #[test]
+    fn matches_by_the_first_tier_that_finds_any() {
+        let settings = settings();
+        let cases: &[(&str, &str, &[&str])] = &[
+            ("index", "1", &["Deep Work"]),
+            ("index out of range", "9", &[]),
+            ("name", "WORK", &["Work"]),
+            ("exact shortcut", "m", &["Email"]),
+            ("exact shortcut", "M", &["Walk"]),
+            ("shortcut ignoring case", "w", &["Work"]),
+            ("shortcut ignoring case", "d", &["Deep Work"]),
+            ("prefix", "em", &["Email"]),
+            ("prefix", "wo", &["Work"]),
+            ("initials", "dw", &["Deep Work"]),
+            ("subsequence", "wlk", &["Walk"]),
+            ("subsequence", "ork", &["Work", "Deep Work"]),
+            ("nothing", "xyz", &[]),
+            ("nothing", " ", &[]),
+        ];
+        for (tier, text, expected) in cases {
+            assert_eq!(names(&settings, text), *expected, "{} {:?}", tier, text);
         }
-        Ok(())
+    }
+
+    #[test]
+    fn finds_only_a_single_match() {
+        let settings = settings();
+        assert_eq!(Activity::find(&settings, "wlk").map(|a| a.name), Some("Walk".to_string()));
+        assert_eq!(Activity::find(&settings, "ork"), None);
     }
 }
@@ -0,0 +1,210 @@
+use chrono::{Datelike, NaiveDate, Weekday};
+use colored::Colorize;
+use itertools::Itertools;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer};
+use serde_derive::{Deserialize, Serialize};
+use crate::day::Day;
+use crate::settings::Settings;
+use crate::PRODUCTIVE_TARGET;
+
+/// Configuration of the flex-time account.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BalanceSettings {
+    /// First day that counts towards the balance.
+    #[serde(deserialize_with = "deserialize_date")]
+    pub start: NaiveDate,
+    /// Balance in hours before `start`.
+    #[serde(default)]
+    pub initial_balance: f32,
+    /// Days on which `PRODUCTIVE_TARGET` hours are expected.
+    #[serde(default = "default_workdays")]
+    pub workdays: Vec<Weekday>,
+    /// Public holidays, vacation, sick days, ...: no hours are expected.
+    #[serde(default, deserialize_with = "deserialize_dates")]
+    pub holidays: Vec<NaiveDate>,
+}
+
+/// A date written as a TOML date (`2025-01-01`) or as a string
+/// (`"2025-01-01"`).
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DateValue {
+    Toml(toml::value::Datetime),
+    Text(String),
+}
+
+impl DateValue {
+    fn to_date<E: Error>(&self) -> Result<NaiveDate, E> {
+        let text = match self {
+            DateValue::Toml(datetime) => datetime.to_string(),
+            DateValue::Text(text) => text.clone(),
+        };
+        NaiveDate::parse_from_str(&text, "%Y-%m-%d")
+            .map_err(|_| E::custom(format!("invalid date '{}', expected YYYY-MM-DD", text)))
+    }
+}
+
+fn deserialize_date<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDate, D::Error> {
+    DateValue::deserialize(deserializer)?.to_date()
+}
+
+fn deserialize_dates<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<NaiveDate>, D::Error> {
+    Vec::<DateValue>::deserialize(deserializer)?.iter().map(DateValue::to_date).collect()
+}
+
+pub fn default_workdays() -> Vec<Weekday> {
+    vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri]
+}
+
+impl BalanceSettings {
+    pub fn target(&self, date: NaiveDate) -> f32 {
+        if self.workdays.contains(&date.weekday()) && !self.holidays.contains(&date) {
+            PRODUCTIVE_TARGET
+        } else {
+            0.
+        }
+    }
+}
+
+/// Contribution of a single day to the balance.
+pub struct Entry {
+    pub date: NaiveDate,
+    pub hours: f32,
+    pub target: f32,
+}
+
+impl Entry {
+    pub fn difference(&self) -> f32 {
+        self.hours - self.target
+    }
+}
+
+/// All days from the start of the account up to (excluding) `until`. Days
+/// without a data file count as zero hours.
+pub fn entries(settings: &Settings, balance: &BalanceSettings, until: NaiveDate) -> Vec<Entry> {
+    balance
+        .start
+        .iter_days()
+        .take_while(|date| *date < until)
+        .map(|date| {
+            let file = settings.get_filename_by_day(date);
+            let hours = if file.exists() { Day::read(&file).hours_productive() } else { 0. };
+            Entry { date, hours, target: balance.target(date) }
+        })
+        .collect()
+}
+
+fn format_balance(hours: f32) -> String {
+    let text = format!("{:+.2} hrs.", hours);
+    if hours < 0. {
+        text.red().to_string()
+    } else {
+        text.green().to_string()
+    }
+}
+
+/// Print the running balance per week and per month, followed by the current
+/// balance. The current day is not included, as it is not over yet.
+pub fn print_balance(settings: &Settings, balance: &BalanceSettings, today: NaiveDate) {
+    let entries = entries(settings, balance, today);
+
+    let print_periods = |title: &str, key: &dyn Fn(NaiveDate) -> String| {
+        println!("{}", title.bold());
+        let mut running = balance.initial_balance;
+        for (period, group) in &entries.iter().group_by(|e| key(e.date)) {
+            let group = group.collect_vec();
+            let hours: f32 = group.iter().map(|e| e.hours).sum();
+            let target: f32 = group.iter().map(|e| e.target).sum();
+            running += hours - target;
+            println!(
+                "{:10} {:6.2} / {:6.2} hrs. {:>16}  Balance: {}",
+                period,
+                hours,
+                target,
+                format_balance(hours - target),
+                format_balance(running)
+            );
+        }
+    };
+    print_periods("By week:", &|date| {
+        let week = date.iso_week();
+        format!("{}-W{:02}", week.year(), week.week())
+    });
+    println!();
+    print_periods("By month:", &|date| format!("{}-{:02}", date.year(), date.month()));
+    println!();
+
+    let total = balance.initial_balance + entries.iter().map(Entry::difference).sum::<f32>();
+    println!("Balance since {}: {}", balance.start, format_balance(total));
+    println!("(Initial balance: {:+.2} hrs.; today is not included.)", balance.initial_balance);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use super::*;
+    use crate::day::Slot;
+
+    fn date(text: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn reads_toml_dates_and_strings() {
+        let balance: BalanceSettings = toml::from_str(
+            "start = 2025-03-24\nholidays = [2025-03-26, \"2025-03-27\"]",
+        )
+        .unwrap();
+        assert_eq!(balance.start, date("2025-03-24"));
+        assert_eq!(balance.holidays, [date("2025-03-26"), date("2025-03-27")]);
+        assert_eq!(balance.workdays, default_workdays());
+
+        let balance: BalanceSettings = toml::from_str("start = \"2025-03-24\"").unwrap();
+        assert_eq!(balance.start, date("2025-03-24"));
+        assert!(balance.holidays.is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_dates() {
+        assert!(toml::from_str::<BalanceSettings>("start = \"24.03.2025\"").is_err());
+        assert!(toml::from_str::<BalanceSettings>("start = 2025-03-24T09:00:00").is_err());
+        assert!(toml::from_str::<BalanceSettings>("start = 2025-03-24\nholidays = [1]").is_err());
+    }
+
+    #[test]
+    fn expects_no_hours_on_holidays_and_other_weekdays() {
+        let balance: BalanceSettings =
+            toml::from_str("start = 2025-03-24\nworkdays = [\"Mon\", \"Wed\"]\nholidays = [2025-03-31]").unwrap();
+        assert_eq!(balance.target(date("2025-03-24")), PRODUCTIVE_TARGET);
+        assert_eq!(balance.target(date("2025-03-25")), 0.);
+        assert_eq!(balance.target(date("2025-03-26")), PRODUCTIVE_TARGET);
+        assert_eq!(balance.target(date("2025-03-29")), 0.);
+        assert_eq!(balance.target(date("2025-03-31")), 0.);
+    }
+
+    #[test]
+    fn counts_the_days_before_until() {
+        let mut settings = Settings::with_activities(&["Work"]);
+        settings.data_dir = std::env::temp_dir().join(format!("time-tracker-balance-{}", std::process::id()));
+        fs::create_dir_all(&settings.data_dir).unwrap();
+        let mut day = Day::default();
+        for slot in *Slot::from_time(9, 0)..*Slot::from_time(15, 0) {
+            day.time_slots[slot] = Some(settings.activities[0].clone());
+        }
+        day.write(&settings.get_filename_by_day(date("2025-03-24")));
+
+        let balance: BalanceSettings =
+            toml::from_str("start = 2025-03-24\ninitial_balance = 1.5\nholidays = [2025-03-26]").unwrap();
+        let entries = entries(&settings, &balance, date("2025-03-31"));
+        fs::remove_dir_all(&settings.data_dir).unwrap();
+
+        assert_eq!(entries.len(), 7);
+        assert_eq!(entries.first().unwrap().date, date("2025-03-24"));
+        assert_eq!(entries.last().unwrap().date, date("2025-03-30"));
+        assert_eq!(entries.iter().map(|e| e.hours).collect_vec(), [6., 0., 0., 0., 0., 0., 0.]);
+        // Monday to Friday without the holiday.
+        assert_eq!(entries.iter().map(|e| e.target).sum::<f32>(), 4. * PRODUCTIVE_TARGET);
+        assert_eq!(entries.iter().map(Entry::difference).sum::<f32>(), 6. - 32.);
+    }
+}
@@ -0,0 +1,388 @@
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use chrono::NaiveDate;
+use serde_derive::{Deserialize, Serialize};
+use crate::day::{Day, Rounding};
+use crate::report::escape;
+use crate::SLOTS_PER_HOUR;
+
+pub const INVOICES_FILENAME: &str = "invoices.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Client {
+    pub name: String,
+    pub rate: f32,
+    pub currency: String,
+    /// Names of the activities that are billed to this client.
+    pub activities: Vec<String>,
+    /// Each line item is rounded to a multiple of this many minutes.
+    #[serde(default = "default_round_to_minutes")]
+    pub round_to_minutes: usize,
+    #[serde(default)]
+    pub rounding: Rounding,
+}
+
+fn default_round_to_minutes() -> usize {
+    60 / SLOTS_PER_HOUR
+}
+
+impl Client {
+    pub fn round(&self, minutes: usize) -> usize {
+        self.rounding.round(minutes, self.round_to_minutes)
+    }
+}
+
+/// A range of days that has been invoiced to a client.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InvoiceRecord {
+    pub number: String,
+    pub client: String,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub created: NaiveDate,
+}
+
+impl InvoiceRecord {
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.from <= date && date <= self.to
+    }
+}
+
+/// Split `dates` into the days that were not invoiced to `client` yet and
+/// the ones that were.
+pub fn partition_invoiced(
+    records: &[InvoiceRecord],
+    client: &Client,
+    dates: Vec<NaiveDate>,
+) -> (Vec<NaiveDate>, Vec<NaiveDate>) {
+    dates
+        .into_iter()
+        .partition(|date| !records.iter().any(|r| r.client == client.name && r.contains(*date)))
+}
+
+pub fn read_records(path: &Path) -> Vec<InvoiceRecord> {
+    if path.exists() {
+        serde_json::from_str(fs::read_to_string(path).expect("could not read file").as_str())
+            .expect("deserialize")
+    } else {
+        vec![]
+    }
+}
+
+pub fn write_records(path: &Path, records: &[InvoiceRecord]) {
+    fs::write(path, serde_json::to_string_pretty(records).unwrap()).expect("write failed");
+}
+
+pub struct LineItem {
+    pub date: NaiveDate,
+    pub description: String,
+    pub minutes: usize,
+}
+
+pub struct Invoice<'c> {
+    pub number: String,
+    pub client: &'c Client,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub items: Vec<LineItem>,
+}
+
+impl<'c> Invoice<'c> {
+    /// Collect the billable slots of `client` from the given days. Entries are
+    /// billed under their comment, or under the activity name if they have
+    /// none. Line items are rounded per day.
+    pub fn new(
+        number: String,
+        client: &'c Client,
+        from: NaiveDate,
+        to: NaiveDate,
+        days: &[(NaiveDate, Day)],
+    ) -> Self {
+        let minutes_per_slot = 60 / SLOTS_PER_HOUR;
+        let mut items: Vec<LineItem> = Vec::new();
+        for (date, day) in days {
+            let mut day_items: Vec<LineItem> = Vec::new();
+            for (s, e, act) in day.entries() {
+                if !client.activities.contains(&act.name) {
+                    continue;
+                }
+                let text = act.comment.clone().unwrap_or_else(|| act.name.clone());
+                let minutes = (*e - *s) * minutes_per_slot;
+                if let Some(item) = day_items.iter_mut().find(|i| i.description == text) {
+                    item.minutes += minutes;
+                } else {
+                    day_items.push(LineItem { date: *date, description: text, minutes });
+                }
+            }
+            for item in day_items.iter_mut() {
+                item.minutes = client.round(item.minutes);
+            }
+            items.extend(day_items.into_iter().filter(|i| i.minutes > 0));
+        }
+        Invoice { number, client, from, to, items }
+    }
+
+    /// The last day with a line item, which ends the invoiced range.
+    pub fn last_date(&self) -> NaiveDate {
+        self.items.last().map_or(self.to, |i| i.date)
+    }
+
+    pub fn hours(&self) -> f32 {
+        self.items.iter().map(|i| i.minutes).sum::<usize>() as f32 / 60.
+    }
+
+    pub fn total(&self) -> f32 {
+        self.hours() * self.client.rate
+    }
+
+    fn amount(&self, item: &LineItem) -> f32 {
+        item.minutes as f32 / 60. * self.client.rate
+    }
+
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        writeln!(&mut text, "INVOICE {}", self.number).expect("write");
+        writeln!(&mut text, "Client: {}", self.client.name).expect("write");
+        writeln!(&mut text, "Period: {} - {}", self.from, self.to).expect("write");
+        writeln!(&mut text).expect("write");
+        for item in &self.items {
+            writeln!(
+                &mut text,
+                "{}  {:<50} {:6.2} h  {:10.2} {}",
+                item.date,
+                item.description,
+                item.minutes as f32 / 60.,
+                self.amount(item),
+                self.client.currency
+            )
+            .expect("write");
+        }
+        writeln!(&mut text).expect("write");
+        writeln!(
+            &mut text,
+            "Total: {:.2} h x {:.2} {} = {:.2} {}",
+            self.hours(),
+            self.client.rate,
+            self.client.currency,
+            self.total(),
+            self.client.currency
+        )
+        .expect("write");
+        text
+    }
+
+    pub fn markdown(&self) -> String {
+        let mut md = String::new();
+        writeln!(&mut md, "# Invoice {}", self.number).expect("write");
+        writeln!(&mut md).expect("write");
+        writeln!(&mut md, "**Client:** {}  ", self.client.name).expect("write");
+        writeln!(&mut md, "**Period:** {} - {}", self.from, self.to).expect("write");
+        writeln!(&mut md).expect("write");
+        writeln!(&mut md, "| Date | Description | Hours | Amount |").expect("write");
+        writeln!(&mut md, "|---|---|---:|---:|").expect("write");
+        for item in &self.items {
+            writeln!(
+                &mut md,
+                "| {} | {} | {:.2} | {:.2} {} |",
+                item.date,
+                item.description.replace('|', "\\|"),
+                item.minutes as f32 / 60.,
+                self.amount(item),
+                self.client.currency
+            )
+            .expect("write");
+        }
+        writeln!(
+            &mut md,
+            "| | **Total** ({:.2} {}/h) | **{:.2}** | **{:.2} {}** |",
+            self.client.rate,
+            self.client.currency,
+            self.hours(),
+            self.total(),
+            self.client.currency
+        )
+        .expect("write");
+        md
+    }
+
+    pub fn html(&self) -> String {
+        let mut html = String::new();
+        writeln!(&mut html, "<!DOCTYPE html>").expect("write");
+        writeln!(&mut html, "<html><head><meta charset=\"utf-8\"><title>Invoice {}</title>", escape(&self.number)).expect("write");
+        writeln!(&mut html, "<style>").expect("write");
+        writeln!(&mut html, "body {{ font-family: sans-serif; }}").expect("write");
+        writeln!(&mut html, "table {{ border-collapse: collapse; }}").expect("write");
+        writeln!(&mut html, "td, th {{ padding: 2px 8px; border-bottom: 1px solid #ccc; }}").expect("write");
+        writeln!(&mut html, "td.num, th.num {{ text-align: right; }}").expect("write");
+        writeln!(&mut html, "</style></head><body>").expect("write");
+        writeln!(&mut html, "<h1>Invoice {}</h1>", escape(&self.number)).expect("write");
+        writeln!(&mut html, "<p>Client: {}<br>Period: {} - {}</p>", escape(&self.client.name), self.from, self.to).expect("write");
+        writeln!(&mut html, "<table><tr><th>Date</th><th>Description</th><th class=\"num\">Hours</th><th class=\"num\">Amount</th></tr>").expect("write");
+        for item in &self.items {
+            writeln!(
+                &mut html,
+                "<tr><td>{}</td><td>{}</td><td class=\"num\">{:.2}</td><td class=\"num\">{:.2} {}</td></tr>",
+                item.date,
+                escape(&item.description),
+                item.minutes as f32 / 60.,
+                self.amount(item),
+                escape(&self.client.currency)
+            )
+            .expect("write");
+        }
+        writeln!(
+            &mut html,
+            "<tr><th></th><th>Total ({:.2} {}/h)</th><th class=\"num\">{:.2}</th><th class=\"num\">{:.2} {}</th></tr>",
+            self.client.rate,
+            escape(&self.client.currency),
+            self.hours(),
+            self.total(),
+            escape(&self.client.currency)
+        )
+        .expect("write");
+        writeln!(&mut html, "</table>").expect("write");
+        writeln!(&mut html, "</body></html>").expect("write");
+        html
+    }
+}
+
+/// Next invoice number of the form `<year>-<n>`, counting per year.
+pub fn next_number(records: &[InvoiceRecord], today: NaiveDate) -> String {
+    let prefix = format!("{}-", today.format("%Y"));
+    let last = records
+        .iter()
+        .filter_map(|r| r.number.strip_prefix(&prefix))
+        .filter_map(|n| n.parse::<usize>().ok())
+        .max()
+        .unwrap_or(0);
+    format!("{}{:03}", prefix, last + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day::Slot;
+    use crate::settings::Settings;
+
+    fn date(text: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+    }
+
+    fn client(rounding: Rounding) -> Client {
+        Client {
+            name: "ACME".to_string(),
+            rate: 80.,
+            currency: "EUR".to_string(),
+            activities: vec!["Work".to_string()],
+            round_to_minutes: 30,
+            rounding,
+        }
+    }
+
+    fn record(settings: &Settings, day: &mut Day, name: &str, from: (usize, usize), to: (usize, usize), comment: &str) {
+        let mut activity = settings.activities.iter().find(|a| a.name == name).unwrap().clone();
+        activity.comment = Some(comment.to_string()).filter(|c| !c.is_empty());
+        for slot in *Slot::from_time(from.0, from.1)..*Slot::from_time(to.0, to.1) {
+            day.time_slots[slot] = Some(activity.clone());
+            activity.comment = None;
+        }
+    }
+
+    fn days() -> Vec<(NaiveDate, Day)> {
+        let settings = Settings::with_activities(&["Work", "Meeting"]);
+        let mut monday = Day::default();
+        record(&settings, &mut monday, "Work", (9, 0), (10, 15), "Feature A");
+        record(&settings, &mut monday, "Meeting", (10, 15), (10, 30), "Standup");
+        record(&settings, &mut monday, "Work", (10, 30), (11, 0), "");
+        record(&settings, &mut monday, "Work", (13, 0), (13, 15), "Feature A");
+        let mut tuesday = Day::default();
+        record(&settings, &mut tuesday, "Work", (9, 0), (9, 15), "");
+        vec![(date("2025-03-24"), monday), (date("2025-03-25"), tuesday)]
+    }
+
+    fn items(invoice: &Invoice) -> Vec<(String, String, usize)> {
+        invoice.items.iter().map(|i| (i.date.to_string(), i.description.clone(), i.minutes)).collect()
+    }
+
+    #[test]
+    fn bills_entries_under_their_comment_or_activity() {
+        let client = client(Rounding::Up);
+        let invoice = Invoice::new("2025-001".to_string(), &client, date("2025-03-24"), date("2025-03-30"), &days());
+        assert_eq!(
+            items(&invoice),
+            [
+                ("2025-03-24".to_string(), "Feature A".to_string(), 90),
+                ("2025-03-24".to_string(), "Work".to_string(), 30),
+                ("2025-03-25".to_string(), "Work".to_string(), 30),
+            ]
+        );
+        assert_eq!(invoice.hours(), 2.5);
+        assert_eq!(invoice.total(), 200.);
+        assert_eq!(invoice.last_date(), date("2025-03-25"));
+    }
+
+    #[test]
+    fn rounds_line_items_per_day() {
+        let down = client(Rounding::Down);
+        let invoice = Invoice::new("2025-001".to_string(), &down, date("2025-03-24"), date("2025-03-30"), &days());
+        // Tuesday's 15 minutes are rounded down to nothing.
+        assert_eq!(
+            items(&invoice),
+            [
+                ("2025-03-24".to_string(), "Feature A".to_string(), 90),
+                ("2025-03-24".to_string(), "Work".to_string(), 30),
+            ]
+        );
+        assert_eq!(invoice.total(), 160.);
+        assert_eq!(invoice.last_date(), date("2025-03-24"));
+
+        let nearest = Client { round_to_minutes: 60, ..client(Rounding::Nearest) };
+        assert_eq!(nearest.round(29), 0);
+        assert_eq!(nearest.round(30), 60);
+        assert_eq!(nearest.round(90), 120);
+        let up = Client { round_to_minutes: 15, ..client(Rounding::Up) };
+        assert_eq!(up.round(0), 0);
+        assert_eq!(up.round(1), 15);
+        assert_eq!(up.round(45), 45);
+    }
+
+    #[test]
+    fn skips_days_that_were_invoiced_to_the_client() {
+        let records = vec![
+            InvoiceRecord {
+                number: "2025-001".to_string(),
+                client: "ACME".to_string(),
+                from: date("2025-03-24"),
+                to: date("2025-03-25"),
+                created: date("2025-03-31"),
+            },
+            InvoiceRecord {
+                number: "2025-002".to_string(),
+                client: "Other".to_string(),
+                from: date("2025-03-26"),
+                to: date("2025-03-26"),
+                created: date("2025-03-31"),
+            },
+        ];
+        let dates = crate::range::days(date("2025-03-23"), date("2025-03-26"));
+        let (open, invoiced) = partition_invoiced(&records, &client(Rounding::Up), dates);
+        assert_eq!(open, [date("2025-03-23"), date("2025-03-26")]);
+        assert_eq!(invoiced, [date("2025-03-24"), date("2025-03-25")]);
+    }
+
+    #[test]
+    fn numbers_invoices_per_year() {
+        let record = |number: &str| InvoiceRecord {
+            number: number.to_string(),
+            client: "ACME".to_string(),
+            from: date("2025-03-24"),
+            to: date("2025-03-24"),
+            created: date("2025-03-24"),
+        };
+        assert_eq!(next_number(&[], date("2025-03-24")), "2025-001");
+        let records = [record("2024-041"), record("2025-007"), record("2025-003"), record("draft")];
+        assert_eq!(next_number(&records, date("2025-12-31")), "2025-008");
+        assert_eq!(next_number(&records, date("2026-01-01")), "2026-001");
+    }
+}
@@ -0,0 +1,235 @@
+use std::cmp::Reverse;
+use std::fmt::Write;
+use std::path::Path;
+use std::process::Command;
+use itertools::Itertools;
+use crate::report::{escape, Report};
+use crate::settings::Settings;
+use crate::{DAY_START, PRODUCTIVE_TARGET, SLOTS_PER_HOUR};
+
+const DAY_WIDTH: usize = 40;
+const MARGIN: usize = 50;
+const PLOT_HEIGHT: usize = 240;
+const HOUR_HEIGHT: usize = 12;
+const TEXT_STYLE: &str = "font-family=\"sans-serif\" font-size=\"11\" fill=\"#333\"";
+
+fn header(svg: &mut String, width: usize, height: usize) {
+    writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">",
+        width, height
+    )
+    .expect("write");
+    writeln!(svg, "<rect width=\"100%\" height=\"100%\" fill=\"white\"/>").expect("write");
+}
+
+/// Day labels below the x axis, one per column.
+fn day_labels(svg: &mut String, report: &Report, y: usize) {
+    for (i, (date, _)) in report.days().iter().enumerate() {
+        writeln!(
+            svg,
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" {}>{}</text>",
+            MARGIN + i * DAY_WIDTH + DAY_WIDTH / 2,
+            y,
+            TEXT_STYLE,
+            date.format("%d.%m.")
+        )
+        .expect("write");
+    }
+}
+
+/// Horizontal grid lines with hour labels, scaled so that `max_hours` fills
+/// the plot.
+fn hour_axis(svg: &mut String, width: usize, max_hours: f32) {
+    let step = if max_hours > 12. { 4 } else { 2 };
+    for hours in (0..=max_hours as usize).step_by(step) {
+        let y = MARGIN + PLOT_HEIGHT - (hours as f32 / max_hours * PLOT_HEIGHT as f32) as usize;
+        writeln!(
+            svg,
+            "<line x1=\"{}\" y1=\"{2}\" x2=\"{}\" y2=\"{2}\" stroke=\"#ddd\"/>",
+            MARGIN,
+            width - MARGIN / 2,
+            y
+        )
+        .expect("write");
+        writeln!(
+            svg,
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"end\" {}>{}</text>",
+            MARGIN - 5,
+            y + 4,
+            TEXT_STYLE,
+            hours
+        )
+        .expect("write");
+    }
+}
+
+/// Stacked bars of the hours spent per activity on each day.
+pub fn activity_bars(settings: &Settings, report: &Report) -> String {
+    let width = MARGIN * 3 / 2 + report.days().len() * DAY_WIDTH;
+    let legend_height = settings.activities.len() * 16;
+    let height = MARGIN * 2 + PLOT_HEIGHT + legend_height;
+    let max_hours = report
+        .recorded_days()
+        .map(|(_, d)| d.time_slots.iter().filter(|s| s.is_some()).count() as f32 / SLOTS_PER_HOUR as f32)
+        .fold(PRODUCTIVE_TARGET, f32::max)
+        .ceil();
+
+    let mut svg = String::new();
+    header(&mut svg, width, height);
+    hour_axis(&mut svg, width, max_hours);
+    for (i, (_, day)) in report.days().iter().enumerate() {
+        let day = match day {
+            Some(day) => day,
+            None => continue,
+        };
+        let mut y = (MARGIN + PLOT_HEIGHT) as f32;
+        for activity in &settings.activities {
+            let bar = day.hours_of(activity) / max_hours * PLOT_HEIGHT as f32;
+            if bar > 0. {
+                y -= bar;
+                writeln!(
+                    &mut svg,
+                    "<rect x=\"{}\" y=\"{:.1}\" width=\"{}\" height=\"{:.1}\" fill=\"{}\"><title>{}: {} hrs.</title></rect>",
+                    MARGIN + i * DAY_WIDTH + 4,
+                    y,
+                    DAY_WIDTH - 8,
+                    bar,
+                    activity.css_color(),
+                    escape(&activity.name),
+                    day.hours_of(activity)
+                )
+                .expect("write");
+            }
+        }
+    }
+    day_labels(&mut svg, report, MARGIN + PLOT_HEIGHT + 15);
+    for (i, activity) in settings.activities.iter().enumerate() {
+        let y = MARGIN * 2 + PLOT_HEIGHT + i * 16;
+        writeln!(
+            &mut svg,
+            "<rect x=\"{}\" y=\"{}\" width=\"10\" height=\"10\" fill=\"{}\"/><text x=\"{}\" y=\"{}\" {}>{}</text>",
+            MARGIN,
+            y - 9,
+            activity.css_color(),
+            MARGIN + 15,
+            y,
+            TEXT_STYLE,
+            escape(&activity.name)
+        )
+        .expect("write");
+    }
+    writeln!(&mut svg, "</svg>").expect("write");
+    svg
+}
+
+/// Productive hours per day as a line, with the target as a dashed line.
+pub fn productive_line(report: &Report) -> String {
+    let width = MARGIN * 3 / 2 + report.days().len() * DAY_WIDTH;
+    let height = MARGIN * 2 + PLOT_HEIGHT;
+    let max_hours = report
+        .recorded_days()
+        .map(|(_, d)| d.hours_productive())
+        .fold(PRODUCTIVE_TARGET, f32::max)
+        .ceil();
+    let y_of = |hours: f32| (MARGIN + PLOT_HEIGHT) as f32 - hours / max_hours * PLOT_HEIGHT as f32;
+
+    let mut svg = String::new();
+    header(&mut svg, width, height);
+    hour_axis(&mut svg, width, max_hours);
+    writeln!(
+        &mut svg,
+        "<line x1=\"{}\" y1=\"{2:.1}\" x2=\"{}\" y2=\"{2:.1}\" stroke=\"#cd3131\" stroke-dasharray=\"6 4\"/>",
+        MARGIN,
+        width - MARGIN / 2,
+        y_of(PRODUCTIVE_TARGET)
+    )
+    .expect("write");
+    let points = report
+        .days()
+        .iter()
+        .enumerate()
+        .filter_map(|(i, (_, day))| {
+            day.as_ref()
+                .map(|d| (MARGIN + i * DAY_WIDTH + DAY_WIDTH / 2, y_of(d.hours_productive())))
+        })
+        .collect_vec();
+    writeln!(
+        &mut svg,
+        "<polyline points=\"{}\" fill=\"none\" stroke=\"#2472c8\" stroke-width=\"2\"/>",
+        points.iter().map(|(x, y)| format!("{},{:.1}", x, y)).join(" ")
+    )
+    .expect("write");
+    for (x, y) in points {
+        writeln!(&mut svg, "<circle cx=\"{}\" cy=\"{:.1}\" r=\"3\" fill=\"#2472c8\"/>", x, y).expect("write");
+    }
+    day_labels(&mut svg, report, MARGIN + PLOT_HEIGHT + 15);
+    writeln!(&mut svg, "</svg>").expect("write");
+    svg
+}
+
+/// One column per day and one row per hour, colored by the activity that
+/// took up most of the hour. The opacity shows how much of the hour was
+/// tracked. Ties go to the activity listed first in the config.
+pub fn heatmap(settings: &Settings, report: &Report) -> String {
+    let width = MARGIN * 3 / 2 + report.days().len() * DAY_WIDTH;
+    let height = MARGIN * 2 + 24 * HOUR_HEIGHT;
+
+    let mut svg = String::new();
+    header(&mut svg, width, height);
+    for hour in 0..24 {
+        writeln!(
+            &mut svg,
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"end\" {}>{:02}:00</text>",
+            MARGIN - 5,
+            MARGIN + hour * HOUR_HEIGHT + HOUR_HEIGHT - 2,
+            TEXT_STYLE,
+            (hour + *DAY_START / SLOTS_PER_HOUR) % 24
+        )
+        .expect("write");
+    }
+    for (i, (_, day)) in report.days().iter().enumerate() {
+        let day = match day {
+            Some(day) => day,
+            None => continue,
+        };
+        for (hour, slots) in day.time_slots.chunks(SLOTS_PER_HOUR).enumerate() {
+            let counts = slots.iter().flatten().counts();
+            let dominant = counts.iter().max_by_key(|(activity, count)| {
+                let position = settings.activities.iter().position(|a| a == **activity);
+                (**count, Reverse(position.unwrap_or(usize::MAX)))
+            });
+            if let Some((activity, count)) = dominant {
+                let tracked = slots.iter().flatten().count();
+                writeln!(
+                    &mut svg,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" fill-opacity=\"{:.2}\"><title>{} ({} min)</title></rect>",
+                    MARGIN + i * DAY_WIDTH + 1,
+                    MARGIN + hour * HOUR_HEIGHT + 1,
+                    DAY_WIDTH - 2,
+                    HOUR_HEIGHT - 2,
+                    activity.css_color(),
+                    tracked as f32 / SLOTS_PER_HOUR as f32,
+                    escape(&activity.name),
+                    count * 60 / SLOTS_PER_HOUR
+                )
+                .expect("write");
+            }
+        }
+    }
+    day_labels(&mut svg, report, MARGIN + 24 * HOUR_HEIGHT + 15);
+    writeln!(&mut svg, "</svg>").expect("write");
+    svg
+}
+
+/// Convert an SVG file to PNG with the configured converter, which is called
+/// as `<svg_converter> <svg> -o <png>` (e.g. rsvg-convert).
+pub fn convert_to_png(settings: &Settings, svg: &Path) -> bool {
+    let png = svg.with_extension("png");
+    Command::new(&settings.svg_converter)
+        .arg(svg)
+        .arg("-o")
+        .arg(&png)
+        .status()
+        .is_ok_and(|status| status.success())
+}
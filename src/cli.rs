@@ -0,0 +1,538 @@
+use std::path::PathBuf;
+use chrono::{Duration, NaiveDate};
+use clap::builder::{PossibleValue, PossibleValuesParser};
+use clap::{Arg, Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use crate::settings::Settings;
+use crate::{get_day, get_today, range};
+
+/// Records activities in 15 minute slots and reports on productivity.
+///
+/// Run without a command to enter the activity since the last recorded one.
+#[derive(Parser)]
+#[command(name = "time-tracker", version, after_help = TIPS)]
+pub struct Cli {
+    /// Config file to use instead of the default one (or TT_CONFIG).
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+    /// Directory of the data files, overriding data_dir from the config.
+    #[arg(long, global = true)]
+    pub data_dir: Option<PathBuf>,
+    /// Day for add, comment, day, edit, path and tui (yesterday and lastday
+    /// look before it), e.g. yesterday, 'last friday', 2025-03-24 or -2d.
+    #[arg(long, global = true, value_parser = parse_day, allow_hyphen_values = true)]
+    pub date: Option<NaiveDate>,
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+}
+
+const TIPS: &str = "\
+Tips:
+  Many commands work with a prefixed count, e.g.: 3s, 3d, 3w, ... (same as --count 3)
+  Statistics, reports and exports take --from/--to (like --date) or --period (e.g. 'last month', Q2, 2025-W12).
+  Set TT_CONFIG or pass --config to override the config file path.";
+
+#[derive(Subcommand)]
+pub enum Commands {
+    /// Record an activity without prompting, e.g.: add Work 09:00-11:30
+    Add {
+        /// Name or shortcut of the activity.
+        activity: String,
+        /// Time span like 09:00-11:30 or 9-now.
+        range: String,
+        /// Comment for the entry.
+        #[arg(long)]
+        comment: Option<String>,
+        /// Tag the entry, may be repeated.
+        #[arg(long)]
+        tag: Vec<String>,
+    },
+    /// Enter an activity for a specific time span.
+    #[command(visible_alias = "a")]
+    Activity {
+        /// Number of activities to enter.
+        #[arg(short = 'n', long, default_value_t = 1)]
+        count: usize,
+    },
+    /// Fill the empty slots of the day from a template, e.g.: apply-template office --date monday
+    ApplyTemplate {
+        name: String,
+        /// Apply without asking, even if some slots are recorded differently.
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Print the flex-time balance by week and month (configure [balance]
+    /// first).
+    Balance,
+    /// Write SVG charts for the last days.
+    Chart {
+        /// Prefix of the output files.
+        #[arg(long)]
+        svg: String,
+        /// Also convert the charts to PNG (see svg_converter).
+        #[arg(long)]
+        png: bool,
+        #[command(flatten)]
+        range: DateRange,
+    },
+    /// Add a comment to the last activity.
+    #[command(visible_alias = "c")]
+    Comment {
+        /// Comment the activity at this time instead of the last one.
+        #[arg(long, allow_hyphen_values = true)]
+        at: Option<String>,
+        /// The comment. Asked for if missing.
+        text: Vec<String>,
+    },
+    /// Check the last seven days against the working-time rules.
+    Compliance {
+        #[command(flatten)]
+        range: DateRange,
+    },
+    /// Remind about unrecorded time with desktop notifications (configure
+    /// [reminder] to change when).
+    Daemon {
+        /// Print the reminders instead of sending notifications.
+        #[arg(long)]
+        print: bool,
+    },
+    /// Print statistics for a specific day (or several days).
+    #[command(visible_alias = "d")]
+    Day {
+        #[command(flatten)]
+        range: DateRange,
+    },
+    /// Print statistics for yesterday (or the day before --date).
+    #[command(visible_alias = "yd")]
+    Yesterday,
+    /// Print statistics for the last recorded day (before today or --date)
+    /// of the month.
+    #[command(visible_alias = "ld")]
+    Lastday,
+    /// Edit activities for a specific day in text editor.
+    #[command(visible_alias = "e")]
+    Edit {
+        /// Edit the plan of the day instead.
+        #[arg(long)]
+        plan: bool,
+    },
+    /// Edit activities for today in text editor.
+    #[command(visible_alias = "ed")]
+    Edittoday {
+        /// Edit the plan of the day instead.
+        #[arg(long)]
+        plan: bool,
+    },
+    /// Export the last days in org-mode format.
+    Export {
+        /// Output format.
+        #[arg(long, value_enum, default_value_t = ExportFormat::Org)]
+        format: ExportFormat,
+        #[command(flatten)]
+        range: DateRange,
+    },
+    /// Ask about each empty span of the day between the first and the last
+    /// entry, e.g.: fill --date yesterday --hours 9-17
+    Fill {
+        /// Working hours like 09:00-17:00 to ask about as well.
+        #[arg(long)]
+        hours: Option<String>,
+    },
+    /// Alternate between focus periods and breaks, e.g.: focus Work 25/5 x4
+    Focus {
+        /// Name or shortcut of the activity.
+        activity: String,
+        /// Minutes of each focus period and break.
+        #[arg(default_value = "25/5", value_parser = parse_durations)]
+        durations: (u64, u64),
+        /// Number of focus periods.
+        #[arg(default_value = "x4", value_parser = parse_cycles)]
+        cycles: usize,
+        /// Activity to record for breaks, defaults to Break if there is one.
+        #[arg(long)]
+        break_activity: Option<String>,
+    },
+    /// Read CLOCK entries from an org-mode file.
+    Import {
+        file: PathBuf,
+    },
+    /// Write an invoice for a client for the last days.
+    Invoice {
+        /// One of the clients from the config file.
+        client: String,
+        /// Output file, defaults to invoice-<number>.<format>.
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// Do not record the invoice, so the days can be invoiced again.
+        #[arg(long)]
+        dry_run: bool,
+        /// Output format.
+        #[arg(long, value_enum, default_value_t = InvoiceFormat::Md)]
+        format: InvoiceFormat,
+        #[command(flatten)]
+        range: DateRange,
+    },
+    /// Print the hours of each activity per day of the last year as JSON.
+    Json {
+        #[command(flatten)]
+        range: DateRange,
+    },
+    /// Print today's data file path.
+    #[command(visible_alias = "p")]
+    Path,
+    /// Write a report for the last days.
+    Report {
+        /// Print a markdown report of the last weeks, or of this calendar
+        /// month and the ones before.
+        #[arg(long, value_enum, required_unless_present = "html", conflicts_with = "html")]
+        markdown: Option<ReportPeriod>,
+        /// Write an HTML report to this file.
+        #[arg(long)]
+        html: Option<PathBuf>,
+        #[command(flatten)]
+        range: DateRange,
+    },
+    /// Compare the plan with what was recorded, for today (or several days).
+    Review {
+        #[command(flatten)]
+        range: DateRange,
+    },
+    /// Save the day, or a time span of it, as a template, e.g.: save-template office 8-17
+    SaveTemplate {
+        name: String,
+        /// Time span like 09:00-11:30, defaults to the whole day.
+        range: Option<String>,
+    },
+    /// Run commands in an interactive session. Use 'date DAY' to switch the
+    /// day they work on.
+    Shell,
+    /// Split the time since the last recorded activity in two (three, ...).
+    /// Without prompting: split 11:00 Work Learn
+    #[command(visible_alias = "s")]
+    Split {
+        /// Number of activities to enter.
+        #[arg(short = 'n', long, default_value_t = 2)]
+        count: usize,
+        /// Times to split at, followed by one activity more than times.
+        args: Vec<String>,
+    },
+    /// Start a timer for an activity. Its time is recorded when it is
+    /// stopped.
+    Start {
+        /// Name or shortcut of the activity.
+        activity: String,
+        /// Comment for the entry.
+        comment: Vec<String>,
+    },
+    /// Print the current activity and today's hours for a status bar.
+    Status {
+        /// Output format.
+        #[arg(long, value_enum, default_value_t = StatusFormat::Plain)]
+        format: StatusFormat,
+    },
+    /// Stop the running timer and record its activity.
+    Stop,
+    /// Stop the running timer and start one for another activity.
+    Switch {
+        /// Name or shortcut of the activity.
+        activity: String,
+        /// Comment for the entry.
+        comment: Vec<String>,
+    },
+    /// Print statistics for today.
+    #[command(visible_alias = "t")]
+    Today,
+    /// Edit a day (today or --date) in a full-screen terminal UI.
+    Tui {
+        /// Edit the plan of the day instead.
+        #[arg(long)]
+        plan: bool,
+    },
+    /// Like split, but only enter the first activity.
+    #[command(visible_alias = "u")]
+    Until,
+    /// Print statistics for the last seven days.
+    #[command(visible_alias = "w")]
+    Week {
+        #[command(flatten)]
+        range: DateRange,
+    },
+    /// Print statistics for the last year.
+    #[command(visible_alias = "y")]
+    Year {
+        #[command(flatten)]
+        range: DateRange,
+    },
+    /// Print a completion script for a shell, e.g.: completions bash >
+    /// ~/.local/share/bash-completion/completions/time-tracker
+    Completions {
+        shell: Shell,
+    },
+    /// Delete today's file.
+    Clear,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ReportPeriod {
+    Week,
+    Month,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ExportFormat {
+    Org,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum InvoiceFormat {
+    Md,
+    Html,
+    Txt,
+}
+
+impl InvoiceFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            InvoiceFormat::Md => "md",
+            InvoiceFormat::Html => "html",
+            InvoiceFormat::Txt => "txt",
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum StatusFormat {
+    Plain,
+    Tmux,
+    WaybarJson,
+}
+
+/// The days a command works on: the last `count` days, weeks, ... or an
+/// explicit range.
+#[derive(Args)]
+pub struct DateRange {
+    /// Number of days, weeks, ... depending on the command.
+    #[arg(short = 'n', long, default_value_t = 1)]
+    pub count: usize,
+    /// First day, like --date.
+    #[arg(long, value_parser = parse_day, allow_hyphen_values = true)]
+    pub from: Option<NaiveDate>,
+    /// Last day, like --date.
+    #[arg(long, value_parser = parse_day, allow_hyphen_values = true)]
+    pub to: Option<NaiveDate>,
+    /// Calendar period like 'last month', Q2, 2025-W12 or 2025-03.
+    #[arg(long, value_parser = parse_period, conflicts_with_all = ["from", "to"])]
+    pub period: Option<(NaiveDate, NaiveDate)>,
+}
+
+impl DateRange {
+    /// Dates given by `--period` or by `--from` and `--to`, defaulting to
+    /// `count` times `days` days up to today.
+    pub fn dates(&self, days: usize) -> Vec<NaiveDate> {
+        if let Some((from, to)) = self.period {
+            return range::days(from, to);
+        }
+        let to = self.to.unwrap_or_else(get_today);
+        let from = self
+            .from
+            .unwrap_or_else(|| to - Duration::days((self.count * days) as i64 - 1));
+        range::days(from, to)
+    }
+
+    /// Like `dates`, but `count` calendar months up to today (or `--to`),
+    /// starting on the first of the month.
+    pub fn months(&self) -> Vec<NaiveDate> {
+        if self.period.is_some() || self.from.is_some() {
+            return self.dates(1);
+        }
+        let to = self.to.unwrap_or_else(get_today);
+        range::days(range::month_start(to, self.count.saturating_sub(1)), to)
+    }
+
+    pub fn is_given(&self) -> bool {
+        self.period.is_some() || self.from.is_some() || self.to.is_some()
+    }
+}
+
+fn parse_day(text: &str) -> Result<NaiveDate, String> {
+    get_day(text).ok_or_else(|| format!("invalid date '{}'", text))
+}
+
+fn parse_period(text: &str) -> Result<(NaiveDate, NaiveDate), String> {
+    range::parse_period(text, get_today()).ok_or_else(|| format!("invalid period '{}'", text))
+}
+
+fn parse_durations(text: &str) -> Result<(u64, u64), String> {
+    let (work, rest) = text.split_once('/').ok_or("expected FOCUS/BREAK minutes, like 25/5")?;
+    Ok((
+        work.parse().map_err(|_| format!("invalid minutes '{}'", work))?,
+        rest.parse().map_err(|_| format!("invalid minutes '{}'", rest))?,
+    ))
+}
+
+fn parse_cycles(text: &str) -> Result<usize, String> {
+    text.strip_prefix('x')
+        .unwrap_or(text)
+        .parse()
+        .map_err(|_| format!("expected a number like x4, got '{}'", text))
+}
+
+/// Global options that take a value, to find the command in the arguments.
+const GLOBAL_OPTIONS: [&str; 3] = ["--config", "--data-dir", "--date"];
+
+/// Rewrite the legacy shorthand before parsing: a count prefix like `3w`
+/// becomes `w --count 3` (and is dropped for commands without a count), and
+/// `h` becomes `help`.
+pub fn expand_shorthand(args: impl Iterator<Item = String>) -> Vec<String> {
+    let mut args: Vec<String> = args.collect();
+    let mut i = 1;
+    while i < args.len() {
+        if GLOBAL_OPTIONS.contains(&args[i].as_str()) {
+            i += 2;
+        } else if args[i].starts_with('-') {
+            i += 1;
+        } else {
+            break;
+        }
+    }
+    if let Some(arg) = args.get(i).cloned() {
+        let digits = arg.chars().take_while(|c| c.is_ascii_digit()).count();
+        let (count, command) = arg.split_at(digits);
+        if command == "h" {
+            args[i] = "help".to_string();
+        } else if !count.is_empty() && !command.is_empty() && command.chars().all(|c| c.is_alphabetic()) {
+            let takes_count = Cli::command()
+                .find_subcommand(command)
+                .is_some_and(|c| c.get_arguments().any(|a| a.get_id() == "count"));
+            args[i] = command.to_string();
+            if takes_count {
+                args.insert(i + 1, "--count".to_string());
+                args.insert(i + 2, count.to_string());
+            }
+        }
+    }
+    args
+}
+
+/// Days suggested when completing `--date`, `--from` and `--to`.
+const COMPLETION_DAYS: [&str; 9] = [
+    "today",
+    "yesterday",
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+
+/// Print a completion script for `shell`. Besides the commands and flags it
+/// completes the activities (and their shortcuts) from the config, so it has
+/// to be generated again when they change.
+pub fn print_completions(shell: Shell, settings: &Settings) {
+    let activities = settings
+        .activities
+        .iter()
+        .zip(settings.get_shortcuts())
+        .flat_map(|(activity, shortcut)| {
+            let name = PossibleValue::new(activity.name.clone());
+            let shortcut = shortcut.map(|c| PossibleValue::new(c.to_string()).help(activity.name.clone()));
+            std::iter::once(name).chain(shortcut)
+        })
+        .collect::<Vec<_>>();
+    let complete = |arg: Arg| match arg.get_id().as_str() {
+        "activity" | "args" => arg.value_parser(PossibleValuesParser::new(activities.clone())),
+        "date" | "from" | "to" => arg.value_parser(PossibleValuesParser::new(COMPLETION_DAYS)),
+        _ => arg,
+    };
+    let mut command = Cli::command()
+        .mut_args(&complete)
+        .mut_subcommands(|c| c.mut_args(&complete));
+    // Complete the name the program was called by, e.g. an alias like `tt`.
+    let name = std::env::args()
+        .next()
+        .and_then(|arg| Some(PathBuf::from(arg).file_name()?.to_str()?.to_string()))
+        .unwrap_or_else(|| command.get_name().to_string());
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut command, &name, &mut script);
+    let mut script = String::from_utf8(script).expect("utf-8");
+    if shell == Shell::Bash {
+        // clap_complete mangles the subcommand functions of names with dashes.
+        script = script.replace(&name.replace('-', "__subcmd__"), &name.replace('-', "__"));
+    }
+    print!("{}", script);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(args: &str) -> String {
+        expand_shorthand(args.split(' ').map(str::to_string)).join(" ")
+    }
+
+    #[test]
+    fn expands_count_prefixes() {
+        assert_eq!(expand("tt 3w"), "tt w --count 3");
+        assert_eq!(expand("tt 12d"), "tt d --count 12");
+        assert_eq!(expand("tt 2a"), "tt a --count 2");
+    }
+
+    #[test]
+    fn drops_counts_of_commands_without_one() {
+        assert_eq!(expand("tt 3t"), "tt t");
+        assert_eq!(expand("tt 2path"), "tt path");
+    }
+
+    #[test]
+    fn expands_h_to_help() {
+        assert_eq!(expand("tt h"), "tt help");
+        assert_eq!(expand("tt h w"), "tt help w");
+    }
+
+    #[test]
+    fn skips_global_options() {
+        assert_eq!(expand("tt --date yesterday 3d"), "tt --date yesterday d --count 3");
+        assert_eq!(expand("tt --date -2d 3d"), "tt --date -2d d --count 3");
+        assert_eq!(expand("tt --no-such-flag 3w"), "tt --no-such-flag w --count 3");
+    }
+
+    #[test]
+    fn rejects_unknown_formats() {
+        assert!(Cli::try_parse_from(["tt", "status", "--format", "tmux"]).is_ok());
+        assert!(Cli::try_parse_from(["tt", "invoice", "ACME", "--format", "html"]).is_ok());
+        assert!(Cli::try_parse_from(["tt", "status", "--format", "bogus"]).is_err());
+        assert!(Cli::try_parse_from(["tt", "export", "--format", "md"]).is_err());
+        assert!(Cli::try_parse_from(["tt", "invoice", "ACME", "--format", "org"]).is_err());
+    }
+
+    fn report_range(args: &str) -> DateRange {
+        match Cli::try_parse_from(args.split(' ')).unwrap().command {
+            Some(Commands::Report { range, .. }) => range,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn reports_calendar_months() {
+        let date = |text| NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap();
+        let months = report_range("tt report --markdown month --to 2025-03-15").months();
+        assert_eq!((months[0], *months.last().unwrap()), (date("2025-03-01"), date("2025-03-15")));
+        let months = report_range("tt report --markdown month -n 3 --to 2025-01-31").months();
+        assert_eq!((months[0], *months.last().unwrap()), (date("2024-11-01"), date("2025-01-31")));
+        let months = report_range("tt report --markdown month --period 2024-02").months();
+        assert_eq!((months[0], *months.last().unwrap()), (date("2024-02-01"), date("2024-02-29")));
+    }
+
+    #[test]
+    fn leaves_other_arguments_alone() {
+        assert_eq!(expand("tt"), "tt");
+        assert_eq!(expand("tt w"), "tt w");
+        assert_eq!(expand("tt 3"), "tt 3");
+        assert_eq!(expand("tt 3w2"), "tt 3w2");
+        // Only the command is expanded, not its arguments.
+        assert_eq!(expand("tt split 11:00 n 2d"), "tt split 11:00 n 2d");
+        assert_eq!(expand("tt add Work 9-10"), "tt add Work 9-10");
+    }
+}
@@ -0,0 +1,344 @@
+use std::fmt::{self, Display, Formatter};
+use chrono::NaiveDate;
+use colored::Colorize;
+use serde_derive::{Deserialize, Serialize};
+use crate::activity::Activity;
+use crate::day::{Day, Slot};
+use crate::settings::Settings;
+use crate::SLOTS_PER_HOUR;
+
+/// Minimum total break once the work time of a day exceeds `after_hours`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BreakRule {
+    pub after_hours: f32,
+    pub minutes: usize,
+}
+
+/// Working-time rules. The defaults follow the German Arbeitszeitgesetz.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ComplianceSettings {
+    #[serde(default = "default_max_daily_hours")]
+    pub max_daily_hours: f32,
+    /// Longest stretch of work without a break.
+    #[serde(default = "default_max_continuous_hours")]
+    pub max_continuous_hours: f32,
+    #[serde(default = "default_breaks")]
+    pub breaks: Vec<BreakRule>,
+    /// Minimum time between the end of work on one day and the start of
+    /// work on the next.
+    #[serde(default = "default_min_rest_hours")]
+    pub min_rest_hours: f32,
+    /// Activities that count as work. Defaults to all productive activities.
+    #[serde(default)]
+    pub work_activities: Vec<String>,
+    /// Activities that count as a break. Defaults to everything that is not
+    /// work. Empty slots between work always count.
+    #[serde(default)]
+    pub break_activities: Vec<String>,
+}
+
+fn default_max_daily_hours() -> f32 {
+    10.
+}
+
+fn default_max_continuous_hours() -> f32 {
+    6.
+}
+
+fn default_breaks() -> Vec<BreakRule> {
+    vec![
+        BreakRule { after_hours: 6., minutes: 30 },
+        BreakRule { after_hours: 9., minutes: 45 },
+    ]
+}
+
+fn default_min_rest_hours() -> f32 {
+    11.
+}
+
+pub enum Violation {
+    TooMuchWork { date: NaiveDate, hours: f32, max: f32 },
+    NoBreak { date: NaiveDate, start: Slot, end: Slot, max: f32 },
+    ShortBreak { date: NaiveDate, work: f32, minutes: usize, required: usize },
+    ShortRest { date: NaiveDate, hours: f32, min: f32 },
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::TooMuchWork { date, hours, max } => {
+                write!(f, "{}: Worked {} hours, more than {} hours.", date, hours, max)
+            },
+            Violation::NoBreak { date, start, end, max } => write!(
+                f,
+                "{}: Worked from {} to {} without a break, more than {} hours.",
+                date, start, end, max
+            ),
+            Violation::ShortBreak { date, work, minutes, required } => write!(
+                f,
+                "{}: Only {} minutes of break for {} hours of work, {} minutes required.",
+                date, minutes, work, required
+            ),
+            Violation::ShortRest { date, hours, min } => write!(
+                f,
+                "{}: Only {} hours of rest before work started, {} hours required.",
+                date, hours, min
+            ),
+        }
+    }
+}
+
+impl ComplianceSettings {
+    fn is_work(&self, settings: &Settings, activity: &Activity) -> bool {
+        if self.work_activities.is_empty() {
+            settings
+                .activities
+                .iter()
+                .find(|a| *a == activity)
+                .map_or(activity.productive, |a| a.productive)
+        } else {
+            self.work_activities.contains(&activity.name)
+        }
+    }
+
+    fn is_break(&self, settings: &Settings, slot: &Option<Activity>) -> bool {
+        match slot {
+            None => true,
+            Some(activity) if self.break_activities.is_empty() => !self.is_work(settings, activity),
+            Some(activity) => self.break_activities.contains(&activity.name),
+        }
+    }
+
+    fn work_slots(&self, settings: &Settings, day: &Day) -> Vec<usize> {
+        day.time_slots
+            .iter()
+            .enumerate()
+            .filter(|(_, o)| o.as_ref().is_some_and(|a| self.is_work(settings, a)))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Rules that only concern a single day.
+    pub fn check_day(&self, settings: &Settings, date: NaiveDate, day: &Day) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        let work_slots = self.work_slots(settings, day);
+        let (first, last) = match (work_slots.first(), work_slots.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => return violations,
+        };
+        let hours = work_slots.len() as f32 / SLOTS_PER_HOUR as f32;
+        if hours > self.max_daily_hours {
+            violations.push(Violation::TooMuchWork { date, hours, max: self.max_daily_hours });
+        }
+
+        let span = &day.time_slots[first..=last];
+        let break_slots = span
+            .iter()
+            .filter(|o| self.is_break(settings, o))
+            .count();
+        let minutes = break_slots * 60 / SLOTS_PER_HOUR;
+        if let Some(rule) = self
+            .breaks
+            .iter()
+            .filter(|rule| hours > rule.after_hours)
+            .max_by_key(|rule| rule.minutes)
+        {
+            if minutes < rule.minutes {
+                violations.push(Violation::ShortBreak { date, work: hours, minutes, required: rule.minutes });
+            }
+        }
+
+        // Any slot that is neither work nor break (including empty ones) ends
+        // a stretch of work as well.
+        let mut start = first;
+        for i in first..=last + 1 {
+            let working = day
+                .time_slots
+                .get(i)
+                .and_then(|o| o.as_ref())
+                .is_some_and(|a| self.is_work(settings, a));
+            if !working {
+                let stretch = (i - start) as f32 / SLOTS_PER_HOUR as f32;
+                if stretch > self.max_continuous_hours {
+                    violations.push(Violation::NoBreak {
+                        date,
+                        start: Slot(start),
+                        end: Slot(i),
+                        max: self.max_continuous_hours,
+                    });
+                }
+                start = i + 1;
+            }
+        }
+        violations
+    }
+
+    /// The rest period between two consecutive days.
+    pub fn check_rest(
+        &self,
+        settings: &Settings,
+        (date, day): (NaiveDate, &Day),
+        (next_date, next_day): (NaiveDate, &Day),
+    ) -> Option<Violation> {
+        let end = Slot(*self.work_slots(settings, day).last()?).next().to_datetime(date);
+        let start = Slot(*self.work_slots(settings, next_day).first()?).to_datetime(next_date);
+        let hours = (start - end).num_minutes() as f32 / 60.;
+        if hours < self.min_rest_hours {
+            Some(Violation::ShortRest { date: next_date, hours, min: self.min_rest_hours })
+        } else {
+            None
+        }
+    }
+
+    /// Check each of the given days, and each pair of consecutive days.
+    pub fn check(&self, settings: &Settings, days: &[(NaiveDate, Day)]) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        for (i, (date, day)) in days.iter().enumerate() {
+            if i > 0 {
+                let (prev_date, prev_day) = &days[i - 1];
+                if prev_date.succ_opt() == Some(*date) {
+                    violations.extend(self.check_rest(settings, (*prev_date, prev_day), (*date, day)));
+                }
+            }
+            violations.extend(self.check_day(settings, *date, day));
+        }
+        violations
+    }
+}
+
+pub fn print_violations(violations: &[Violation]) {
+    for violation in violations {
+        println!("{}: {}", "Compliance".red(), violation);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> Settings {
+        let mut settings = Settings::with_activities(&["Work", "Break", "Chores"]);
+        settings.activities[1].productive = false;
+        settings.activities[2].productive = false;
+        settings
+    }
+
+    /// The rules as they are without a `[compliance]` table in the config.
+    fn rules() -> ComplianceSettings {
+        toml::from_str("").unwrap()
+    }
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, 24).unwrap()
+    }
+
+    fn record(settings: &Settings, day: &mut Day, name: &str, from: (usize, usize), to: (usize, usize)) {
+        let activity = settings.activities.iter().find(|a| a.name == name).unwrap();
+        for slot in *Slot::from_time(from.0, from.1)..*Slot::from_time(to.0, to.1) {
+            day.time_slots[slot] = Some(activity.clone());
+        }
+    }
+
+    fn check(rules: &ComplianceSettings, settings: &Settings, day: &Day) -> Vec<String> {
+        rules.check_day(settings, date(), day).iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn counts_recorded_breaks() {
+        let settings = settings();
+        let mut day = Day::default();
+        record(&settings, &mut day, "Work", (9, 0), (17, 0));
+        record(&settings, &mut day, "Break", (12, 0), (12, 30));
+        assert!(check(&rules(), &settings, &day).is_empty());
+    }
+
+    #[test]
+    fn counts_empty_slots_between_work_as_break() {
+        let settings = settings();
+        let mut day = Day::default();
+        record(&settings, &mut day, "Work", (9, 0), (12, 0));
+        record(&settings, &mut day, "Work", (13, 0), (17, 0));
+        assert!(check(&rules(), &settings, &day).is_empty());
+    }
+
+    #[test]
+    fn requires_longer_breaks_for_longer_days() {
+        let settings = settings();
+        let mut day = Day::default();
+        record(&settings, &mut day, "Work", (9, 0), (16, 15));
+        record(&settings, &mut day, "Break", (12, 0), (12, 15));
+        assert_eq!(
+            check(&rules(), &settings, &day),
+            ["2025-03-24: Only 15 minutes of break for 7 hours of work, 30 minutes required."]
+        );
+
+        let mut day = Day::default();
+        record(&settings, &mut day, "Work", (7, 0), (17, 30));
+        record(&settings, &mut day, "Break", (12, 0), (12, 30));
+        assert_eq!(
+            check(&rules(), &settings, &day),
+            ["2025-03-24: Only 30 minutes of break for 10 hours of work, 45 minutes required."]
+        );
+    }
+
+    #[test]
+    fn flags_long_days_and_long_stretches() {
+        let settings = settings();
+        let mut day = Day::default();
+        record(&settings, &mut day, "Work", (6, 0), (12, 15));
+        record(&settings, &mut day, "Chores", (12, 15), (13, 0));
+        record(&settings, &mut day, "Work", (13, 0), (17, 15));
+        assert_eq!(
+            check(&rules(), &settings, &day),
+            [
+                "2025-03-24: Worked 10.5 hours, more than 10 hours.",
+                "2025-03-24: Worked from 06:00 to 12:15 without a break, more than 6 hours.",
+            ]
+        );
+    }
+
+    #[test]
+    fn only_counts_configured_break_activities() {
+        let settings = settings();
+        let mut day = Day::default();
+        record(&settings, &mut day, "Work", (9, 0), (12, 0));
+        record(&settings, &mut day, "Chores", (12, 0), (12, 30));
+        record(&settings, &mut day, "Work", (12, 30), (17, 0));
+        assert!(check(&rules(), &settings, &day).is_empty());
+
+        let rules = ComplianceSettings { break_activities: vec!["Break".to_string()], ..rules() };
+        assert_eq!(
+            check(&rules, &settings, &day),
+            ["2025-03-24: Only 0 minutes of break for 7.5 hours of work, 30 minutes required."]
+        );
+    }
+
+    #[test]
+    fn only_counts_configured_work_activities() {
+        let settings = settings();
+        let mut day = Day::default();
+        record(&settings, &mut day, "Chores", (6, 0), (18, 0));
+        assert!(check(&rules(), &settings, &day).is_empty());
+
+        let rules = ComplianceSettings { work_activities: vec!["Chores".to_string()], ..rules() };
+        assert_eq!(check(&rules, &settings, &day).len(), 3);
+    }
+
+    #[test]
+    fn checks_the_rest_between_consecutive_days() {
+        let settings = settings();
+        let mut evening = Day::default();
+        record(&settings, &mut evening, "Work", (16, 0), (22, 0));
+        let mut morning = Day::default();
+        record(&settings, &mut morning, "Work", (8, 0), (12, 0));
+        let next = date().succ_opt().unwrap();
+        let violations = rules().check(&settings, &[(date(), evening.clone()), (next, morning.clone())]);
+        assert_eq!(
+            violations.iter().map(|v| v.to_string()).collect::<Vec<_>>(),
+            ["2025-03-25: Only 10 hours of rest before work started, 11 hours required."]
+        );
+        // Days that do not follow each other are not compared.
+        let later = next.succ_opt().unwrap();
+        assert!(rules().check(&settings, &[(date(), evening), (later, morning)]).is_empty());
+    }
+}
@@ -1,11 +1,9 @@
 use serde_derive::{Deserialize, Serialize};
 use std::path::Path;
-use std::{fmt, fs, io};
-use chrono::{Local, Timelike};
-use std::convert::TryFrom;
-use std::io::ErrorKind;
+use std::{fmt, fs};
 use std::ops::Deref;
 use std::fmt::{Display, Formatter};
+use chrono::{Duration, NaiveDate, NaiveDateTime, Timelike};
 use colored::Colorize;
 use itertools::Itertools;
 use crate::activity::Activity;
@@ -15,9 +13,32 @@ use crate::settings::Settings;
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub struct Slot(pub usize);
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Rounding {
+    #[default]
+    Up,
+    Nearest,
+    Down,
+}
+
+impl Rounding {
+    /// Round `value` to a multiple of `step`.
+    pub fn round(self, value: usize, step: usize) -> usize {
+        let step = step.max(1);
+        let steps = match self {
+            Rounding::Up => value.div_ceil(step),
+            Rounding::Nearest => (value + step / 2) / step,
+            Rounding::Down => value / step,
+        };
+        steps * step
+    }
+}
+
 impl Slot {
     #[cfg(not(test))]
     pub fn now() -> Slot {
+        use chrono::{Local, Timelike};
         let local = Local::now();
         let hour = local.hour();
         let minute = local.minute();
@@ -42,6 +63,23 @@ impl Slot {
     pub fn next(&self) -> Slot {
         Slot(self.0 + 1)
     }
+
+    /// Wall-clock time at which this slot starts on the given day.
+    pub fn to_datetime(self, date: NaiveDate) -> NaiveDateTime {
+        let minutes_per_slot = 60 / SLOTS_PER_HOUR;
+        date.and_hms_opt(0, 0, 0).unwrap()
+            + Duration::minutes(((self.0 + *DAY_START) * minutes_per_slot) as i64)
+    }
+
+    /// The day (as used for data files) and slot a wall-clock time falls into.
+    /// Times before `DAY_START` belong to the previous day.
+    pub fn from_datetime(time: NaiveDateTime) -> (NaiveDate, Slot) {
+        let day_start = Duration::hours((*DAY_START / SLOTS_PER_HOUR) as i64);
+        (
+            (time - day_start).date(),
+            Slot::from_time(time.hour() as usize, time.minute() as usize),
+        )
+    }
 }
 
 impl Deref for Slot {
@@ -52,83 +90,63 @@ impl Deref for Slot {
     }
 }
 
-impl TryFrom<String> for Slot {
-    type Error = Box<dyn std::error::Error>;
-
-    fn try_from(text: String) -> Result<Self, Self::Error> {
-        let hrs: usize;
-        let min: usize;
-        if text == "now" || text == "n" || text.is_empty() {
-            return Ok(Slot::now());
-        }
-        if let Some((text_hrs, text_min)) = text.split_once(":") {
-            hrs = text_hrs.parse()?;
-            min = text_min.parse().unwrap_or(0);
-        } else {
-            hrs = text.parse()?;
-            min = 0;
-        }
-        if hrs > 23 || min > 59 {
-            Err(Box::new(io::Error::new(
-                ErrorKind::InvalidInput,
-                "out of range",
-            )))
-        } else {
-            Ok(Slot::from_time(hrs, min))
-        }
-    }
-}
-
 impl Display for Slot {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         let shifted = (self.deref() + *DAY_START) % DAY_SLOTS;
         let hour = shifted / SLOTS_PER_HOUR;
         let minutes = (shifted % SLOTS_PER_HOUR) * (60 / SLOTS_PER_HOUR);
-        f.write_str(&*format!("{:02}:{:02}", hour, minutes))?;
+        f.write_str(&format!("{:02}:{:02}", hour, minutes))?;
         Ok(())
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Day {
     pub time_slots: Vec<Option<Activity>>,
+    /// Completed sessions of the `focus` command.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub focus_sessions: usize,
+    /// What was planned for each slot. Only written if anything is planned.
+    #[serde(default = "empty_slots", skip_serializing_if = "is_unplanned")]
+    pub planned: Vec<Option<Activity>>,
+}
+
+fn empty_slots() -> Vec<Option<Activity>> {
+    (0..DAY_SLOTS).map(|_| None).collect()
+}
+
+fn is_zero(value: &usize) -> bool {
+    *value == 0
+}
+
+fn is_unplanned(planned: &[Option<Activity>]) -> bool {
+    planned.iter().all(Option::is_none)
 }
 
 impl Default for Day {
     fn default() -> Self {
         Day {
-            time_slots: (0..DAY_SLOTS).into_iter().map(|_| None).collect(),
+            time_slots: empty_slots(),
+            focus_sessions: 0,
+            planned: empty_slots(),
         }
     }
 }
 
 impl Day {
     pub fn entry_before_now(&self) -> Option<(Slot, &Activity)> {
-        self.time_slots
-            .iter()
-            .take(*Slot::now())
-            .enumerate()
-            .rev()
-            .find(|(_s, o)| o.is_some())
-            .map(|(s, o)| (Slot(s), o.as_ref().unwrap()))
+        self.entry_before(Slot::now())
     }
 
-    pub fn entry_before_now_mut(&mut self) -> Option<(Slot, &mut Activity)> {
+    /// The last non-empty slot before `slot`.
+    pub fn entry_before(&self, slot: Slot) -> Option<(Slot, &Activity)> {
         self.time_slots
-            .iter_mut()
-            .take(*Slot::now())
+            .iter()
+            .take(*slot)
             .enumerate()
             .rev()
             .find(|(_s, o)| o.is_some())
-            .map(|(s, o)| (Slot(s), o.as_mut().unwrap()))
-    }
-
-    pub fn slots<'a>(&'a self) -> impl Iterator<Item = (Slot, Slot, Option<Activity>)> + 'a {
-        self.time_slots
-            .iter()
-            .cloned()
-            .enumerate()
-            .map(|(s, o)| (Slot(s), Slot(s).next(), o))
+            .map(|(s, o)| (Slot(s), o.as_ref().unwrap()))
     }
 
     pub fn slots_collapsed<'a>(&'a self) -> impl Iterator<Item = (Slot, Slot, Option<Activity>)> + 'a {
@@ -138,8 +156,12 @@ impl Day {
             .enumerate()
             .scan(None, |state: &mut Option<(usize, Option<Activity>)>, (i, o)| {
                 if let Some((start, act)) = state {
-                    // Split if activities are different or comments are different
-                    if *act != o || act.as_ref().zip(o.as_ref()).map_or(false, |(a, b)| a.comment != b.comment && b.comment.is_some()) {
+                    // Split if activities, comments or whether they come from a routine are different
+                    if *act != o
+                        || act.as_ref().zip(o.as_ref()).is_some_and(|(a, b)| {
+                            (a.comment != b.comment && b.comment.is_some()) || a.routine != b.routine
+                        })
+                    {
                         let result = Some(Some((Slot(*start), Slot(i), act.clone())));
                         *state = Some((i, o));
                         result
@@ -151,7 +173,46 @@ impl Day {
                     Some(None)
                 }
             })
-            .filter_map(|o| o)
+            .flatten()
+    }
+
+    /// All non-empty ranges of equal activities, including one that runs until
+    /// the end of the day. A slot with a comment, or from a routine where the
+    /// one before is not (or the other way round), starts a new range.
+    pub fn entries(&self) -> Vec<(Slot, Slot, &Activity)> {
+        let mut entries: Vec<(Slot, Slot, &Activity)> = Vec::new();
+        for (i, o) in self.time_slots.iter().enumerate() {
+            if let Some(act) = o {
+                match entries.last_mut() {
+                    Some((_, end, last)) if **end == i && *last == act && act.comment.is_none() && last.routine == act.routine => {
+                        *end = Slot(i + 1);
+                    },
+                    _ => entries.push((Slot(i), Slot(i + 1), act)),
+                }
+            }
+        }
+        entries
+    }
+
+    /// The planned slots if `plan`, or else the recorded ones.
+    pub fn layer(&self, plan: bool) -> &Vec<Option<Activity>> {
+        if plan {
+            &self.planned
+        } else {
+            &self.time_slots
+        }
+    }
+
+    pub fn layer_mut(&mut self, plan: bool) -> &mut Vec<Option<Activity>> {
+        if plan {
+            &mut self.planned
+        } else {
+            &mut self.time_slots
+        }
+    }
+
+    pub fn is_planned(&self) -> bool {
+        !is_unplanned(&self.planned)
     }
 
     pub fn first_non_empty(&self) -> Option<Slot> {
@@ -175,8 +236,17 @@ impl Day {
             / SLOTS_PER_HOUR as f32
     }
 
+    pub fn hours_of(&self, activity: &Activity) -> f32 {
+        self.time_slots
+            .iter()
+            .filter_map(|it| it.as_ref())
+            .filter(|it| *it == activity)
+            .count() as f32
+            / SLOTS_PER_HOUR as f32
+    }
+
     pub fn score(&self) -> f32 {
-        self.hours_productive() as f32 / PRODUCTIVE_TARGET
+        self.hours_productive() / PRODUCTIVE_TARGET
     }
 
     pub fn activity_string(&self, settings: &Settings, step_by: usize) -> String {
@@ -187,7 +257,7 @@ impl Day {
                 s.as_ref()
                     .and_then(|a| {
                         settings
-                            .get_shortcut(&a)
+                            .get_shortcut(a)
                             .map(|s| s.to_string().color(a.color()).to_string())
                     })
                     .unwrap_or_else(|| " ".into())
@@ -217,9 +287,80 @@ impl Day {
             "Hours Productive: {}",
             self.hours_productive()
         );
+        if self.focus_sessions > 0 {
+            println!("Focus Sessions: {}", self.focus_sessions);
+        }
+    }
+
+    /// The recorded day, or a new one with the automatic routines filled in
+    /// if nothing was recorded yet.
+    pub fn load(settings: &Settings, date: NaiveDate) -> Day {
+        let file = settings.get_filename_by_day(date);
+        if file.exists() {
+            Day::read(&file)
+        } else {
+            let mut day = Day::default();
+            crate::routine::fill_auto(settings, date, &mut day);
+            day
+        }
+    }
+
+    pub fn read(path: &Path) -> Day {
+        let day: Day = serde_json::from_str(
+            fs::read_to_string(path)
+                .expect("could not read file")
+                .as_str(),
+        )
+        .expect("deserialize");
+        assert_eq!(day.time_slots.len(), DAY_SLOTS, "Loaded day file {} is invalid.", path.display());
+        assert_eq!(day.planned.len(), DAY_SLOTS, "Loaded day file {} is invalid.", path.display());
+        day
     }
 
     pub fn write(&self, path: &Path) {
         fs::write(path, serde_json::to_string(&self).unwrap()).expect("write failed");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::Settings;
+
+    fn work() -> Activity {
+        Settings::with_activities(&["Work"]).activities[0].clone()
+    }
+
+    #[test]
+    fn reads_back_a_day_written_without_a_plan() {
+        let mut day = Day::default();
+        day.time_slots[*Slot::from_time(9, 0)] = Some(work());
+        let path = std::env::temp_dir().join(format!("time-tracker-unplanned-{}.json", std::process::id()));
+        day.write(&path);
+        let text = fs::read_to_string(&path).unwrap();
+        let read = Day::read(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert!(!text.contains("planned"));
+        assert_eq!(read.planned.len(), DAY_SLOTS);
+        assert!(!read.is_planned());
+        assert_eq!(read.time_slots[*Slot::from_time(9, 0)], Some(work()));
+    }
+
+    #[test]
+    fn pads_the_plan_of_days_from_before_plans() {
+        let json = format!("{{\"time_slots\":[{}]}}", vec!["null"; DAY_SLOTS].join(","));
+        let day: Day = serde_json::from_str(&json).unwrap();
+        assert_eq!(day.planned.len(), DAY_SLOTS);
+        assert_eq!(day.focus_sessions, 0);
+    }
+
+    #[test]
+    fn keeps_the_plan() {
+        let mut day = Day::default();
+        day.planned[*Slot::from_time(9, 0)] = Some(work());
+        let day: Day = serde_json::from_str(&serde_json::to_string(&day).unwrap()).unwrap();
+        assert!(day.is_planned());
+        assert_eq!(day.planned[*Slot::from_time(9, 0)], Some(work()));
+    }
+}
@@ -0,0 +1,66 @@
+use std::io::{self, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+use chrono::{Local, NaiveDateTime};
+use colored::Colorize;
+use crate::activity::Activity;
+use crate::day::{Day, Slot};
+use crate::settings::Settings;
+use crate::timer;
+
+/// Count down `minutes` on a single line, then ring the bell.
+fn countdown(label: &str, minutes: u64) {
+    let end = Instant::now() + Duration::from_secs(minutes * 60);
+    loop {
+        let left = end.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            break;
+        }
+        let secs = left.as_secs() + u64::from(left.subsec_nanos() > 0);
+        print!("\r{} {:02}:{:02} left ", label, secs / 60, secs % 60);
+        io::stdout().flush().expect("flush");
+        thread::sleep(left.min(Duration::from_secs(1)));
+    }
+    println!("\r{} done.        \x07", label);
+}
+
+/// Count a completed session on the day it started.
+fn add_session(settings: &Settings, since: NaiveDateTime) {
+    let (date, _) = Slot::from_datetime(since);
+    let file = settings.get_filename_by_day(date);
+    let mut day = Day::load(settings, date);
+    day.focus_sessions += 1;
+    day.write(&file);
+    println!("{} focus session(s) on {}.", day.focus_sessions, date);
+}
+
+/// `focus ACTIVITY WORK/BREAK xCYCLES`: Alternate between focus periods and
+/// breaks with a countdown, and record them as they end. Breaks are only
+/// recorded if there is a break activity.
+pub fn run(
+    settings: &Settings,
+    name: &str,
+    (work, rest): (u64, u64),
+    cycles: usize,
+    break_name: Option<String>,
+) -> Result<(), String> {
+    let activity = Activity::find(settings, name).ok_or_else(|| format!("Unknown activity: {}", name))?;
+    let break_activity = match break_name {
+        Some(name) => Some(Activity::find(settings, &name).ok_or_else(|| format!("Unknown activity: {}", name))?),
+        None => Activity::find(settings, "Break"),
+    };
+    for cycle in 1..=cycles {
+        let since = Local::now().naive_local();
+        countdown(&format!("{} {}/{}", activity.name.color(activity.color()), cycle, cycles), work);
+        timer::record(settings, &activity, since, Local::now().naive_local());
+        add_session(settings, since);
+        if cycle < cycles {
+            let since = Local::now().naive_local();
+            countdown(&"Break".bright_blue().to_string(), rest);
+            if let Some(break_activity) = &break_activity {
+                timer::record(settings, break_activity, since, Local::now().naive_local());
+            }
+        }
+    }
+    Ok(())
+}
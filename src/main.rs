@@ -3,23 +3,44 @@ use chrono::{Duration, Local};
 use colored::*;
 use directories::BaseDirs;
 use itertools::Itertools;
-use std::borrow::Borrow;
 use std::collections::HashMap;
-use std::convert::TryFrom;
-use std::fmt::{Display, Write as FmtWrite};
-use std::io::{BufRead, Write};
-use std::ops::Deref;
+use std::fmt::Write as FmtWrite;
 use std::path::PathBuf;
 use std::process::Command;
 use std::str::FromStr;
-use std::{fs, io};
+use std::fs;
 use activity::Activity;
+use clap::Parser;
+use cli::{Cli, Commands, ExportFormat, InvoiceFormat, ReportPeriod, StatusFormat};
 use day::{Day, Slot};
+use predict::Prediction;
+use review::Review;
 use settings::Settings;
 
 mod settings;
 mod activity;
 mod day;
+mod balance;
+mod billing;
+mod chart;
+mod cli;
+mod compliance;
+mod focus;
+mod org;
+mod parse;
+mod predict;
+mod prompt;
+mod range;
+mod record;
+mod reminder;
+mod report;
+mod review;
+mod routine;
+mod shell;
+mod status;
+mod template;
+mod timer;
+mod tui;
 
 pub const CONFIG_FILENAME: &str = "ttrc.toml";
 pub const CONFIG_OVERRIDE_ENV_VAR: &str = "TT_CONFIG";
@@ -34,29 +55,107 @@ fn get_input<T>() -> Option<T>
 where
     T: FromStr,
 {
-    print!("?: ");
-    io::stdout().flush().expect("flush");
-    let stdin = io::stdin();
-    let input: T = stdin.lock().lines().next()?.ok()?.parse().ok()?;
-    Some(input)
+    get_input_completing(Vec::new())
+}
+
+/// Like `get_input`, but tab completes from `candidates`.
+fn get_input_completing<T>(candidates: Vec<String>) -> Option<T>
+where
+    T: FromStr,
+{
+    prompt::read_line("?: ", candidates)?.parse().ok()
+}
+
+/// Print the error and exit with a non-zero exit code.
+fn exit_on_error(result: Result<(), String>) {
+    if let Err(error) = result {
+        eprintln!("{}", error.red());
+        std::process::exit(1);
+    }
+}
+
+/// The current day, which only ends at `DAY_START`.
+fn get_today() -> NaiveDate {
+    parse::today(Local::now().naive_local())
+}
+
+/// Parse a day like `yesterday` or `2025-03-24`, see `parse::parse_day`.
+fn get_day(text: &str) -> Option<NaiveDate> {
+    parse::parse_day(text, Local::now().naive_local())
+}
+
+/// Parse a point in time like `14:07` or `30m ago` on the given day, see
+/// `parse::parse_time_on`.
+fn get_time(text: &str, day: NaiveDate, settings: &Settings) -> Option<(NaiveDate, Slot)> {
+    parse::parse_time_on(text, day, Local::now().naive_local(), settings.time_rounding)
 }
 
 fn get_base_dirs() -> BaseDirs {
     BaseDirs::new().expect("base_dirs")
 }
 
+/// Comments of the two weeks up to `date`, the most recent first and without
+/// duplicates, to complete the comment prompt.
+fn previous_comments(settings: &Settings, date: NaiveDate) -> Vec<String> {
+    (0..14)
+        .map(|i| settings.get_filename_by_day(date - Duration::days(i)))
+        .filter(|file| file.exists())
+        .flat_map(|file| {
+            let day = Day::read(&file);
+            day.time_slots.into_iter().rev().flatten().filter_map(|a| a.comment)
+        })
+        .unique()
+        .collect()
+}
+
 struct UI<'d> {
     day: Day,
+    date: NaiveDate,
     file: PathBuf,
     settings: &'d Settings,
 }
 
 impl UI<'_> {
+    /// Load the data file of another day, if it exists.
+    fn switch_day(&mut self, date: NaiveDate) {
+        self.date = date;
+        self.file = self.settings.get_filename_by_day(date);
+        self.reload();
+    }
+
+    /// Read the data file again, e.g. after a command wrote it directly.
+    fn reload(&mut self) {
+        self.day = Day::load(self.settings, self.date);
+    }
+
+    /// Commands that record or show the time up to now only work on today,
+    /// not on another day switched to in the shell.
+    fn require_today(&self, command: &str) -> Result<(), String> {
+        if self.date == get_today() {
+            Ok(())
+        } else {
+            Err(format!("'{}' only works on today, not on {} (use 'date today').", command, self.date))
+        }
+    }
+
+    /// The current slot and what is recorded, running and planned. Nothing on
+    /// other days than today.
     fn print_current_slot_info(&self) {
+        if self.date != get_today() {
+            return;
+        }
         if let Some(entry) = self.day.entry_before_now() {
             
             println!("Recent activity: {} (until {})", entry.1, entry.0.next());
         }
+        if let Some(timer) = timer::read(self.settings) {
+            println!(
+                "Running: {} (since {}, {})",
+                timer.activity,
+                timer.since.format("%H:%M"),
+                timer.elapsed(Local::now().naive_local())
+            );
+        }
         println!(
             "Current slot: {} ({})",
             Slot::now(),
@@ -66,23 +165,25 @@ impl UI<'_> {
                 "no activity so far".bold().to_string()
             }
         );
+        if let Some(planned) = &self.day.planned[*Slot::now()] {
+            println!("Planned: {}", planned);
+        }
     }
 
     fn get_git_commits(&self, start: Slot, end: Slot) -> Vec<String> {
-        let today = Local::now();
         fs::read_dir(&self.settings.git_repos_dir).expect("read dir")
             .filter_map(|entry| entry.ok())
             .filter(|entry| entry.file_type().expect("file type").is_dir())
             .map(|entry| entry.path())
-            .map(|repo| {
-                let repo_name = repo.file_name().unwrap().to_str().unwrap().clone();
+            .flat_map(|repo| {
+                let repo_name = repo.file_name().unwrap().to_str().unwrap();
                 let log = Command::new(&self.settings.git)
                     .arg("log")
                     .arg("--oneline")
                     .arg("--after")
-                    .arg(format!("{}-{:02}-{:02} {}", today.year(), today.month(), today.day(), start.to_string()))
+                    .arg(start.to_datetime(self.date).format("%Y-%m-%d %H:%M").to_string())
                     .arg("--before")
-                    .arg(format!("{}-{:02}-{:02} {}", today.year(), today.month(), today.day(), end.to_string()))
+                    .arg(end.to_datetime(self.date).format("%Y-%m-%d %H:%M").to_string())
                     .arg("--author")
                     .arg(&self.settings.git_author)
                     .current_dir(&repo)
@@ -93,22 +194,76 @@ impl UI<'_> {
                     .map(|line| format!("{}: {}", repo_name, line))
                     .collect::<Vec<String>>()
             })
-            .flatten()
             .collect::<Vec<String>>()
     }
 
+    fn fill(&mut self, start: Slot, end: Slot, activity: &Activity) {
+        for s in *start..*end {
+            self.day.time_slots[s] = Some(activity.clone());
+        }
+    }
+
+    /// Offer the routines that fall into the empty slots between `start` and
+    /// `end`. Returns whether any of them was recorded.
+    fn offer_routines(&mut self, start: Slot, end: Slot) -> bool {
+        let mut filled = false;
+        let date = self.date;
+        for routine in self.settings.routines.iter().filter(|r| r.applies(date)) {
+            if routine.empty_slots(&self.day, start, end).is_empty() {
+                continue;
+            }
+            let Some(activity) = routine.activity(self.settings) else {
+                println!("{}: Unknown activity '{}' in routine!", "Warning".bright_yellow(), routine.activity);
+                continue;
+            };
+            println!(
+                "Record the routine {} from {} - {}? (y/n)",
+                activity,
+                routine.start().to_string().yellow(),
+                routine.end().to_string().yellow()
+            );
+            if let Some("y") = get_input::<String>().as_deref() {
+                filled |= routine.fill(self.settings, &mut self.day, start, end);
+            }
+        }
+        filled
+    }
+
+    /// Ask about the span between `start` and `end`, offering the routines
+    /// first and then asking about the rest of the span piece by piece.
     fn ask_about_activity(&mut self, start: Slot, end: Slot) {
+        if !self.offer_routines(start, end) {
+            self.ask_about_span(start, end);
+            return;
+        }
+        self.save();
+        let gaps = (*start..*end)
+            .filter(|s| self.day.time_slots[*s].is_none())
+            .map(Slot)
+            .collect::<Vec<_>>();
+        let gaps = gaps.iter().fold(Vec::<(Slot, Slot)>::new(), |mut gaps, slot| {
+            match gaps.last_mut() {
+                Some((_, end)) if end == slot => *end = slot.next(),
+                _ => gaps.push((*slot, slot.next())),
+            }
+            gaps
+        });
+        for (start, end) in gaps {
+            self.ask_about_span(start, end);
+        }
+    }
+
+    fn ask_about_span(&mut self, start: Slot, end: Slot) {
         println!(
             "What did you do from {} - {}?",
             start.to_string().yellow(),
             end.to_string().yellow()
         );
 
-        let act = Activity::prompt(&self.settings);
+        let prediction = Prediction::new(self.settings, self.date, &self.day, start, end);
+        let act = Activity::prompt(self.settings, &prediction);
         if let Some(act) = act {
-            for s in *start..*end {
-                self.day.time_slots[s] = Some(act.clone());
-            }
+            self.fill(start, end, act);
 
             let lines = self.get_git_commits(start, end);
             if !lines.is_empty() {
@@ -131,26 +286,101 @@ impl UI<'_> {
         }
     }
 
+    /// Ask about each empty span between the first and the last entry of the
+    /// day, and between `hours` if given. Each one can be recorded as a whole,
+    /// split at a time, or skipped.
+    fn fill_gaps(&mut self, hours: Option<(Slot, Slot)>) {
+        let entries = self.day.entries();
+        let mut first = entries.first().map(|(start, _, _)| **start);
+        let mut last = entries.last().map(|(_, end, _)| **end);
+        if let Some((start, end)) = hours {
+            first = Some(first.map_or(*start, |first| first.min(*start)));
+            last = Some(last.map_or(*end, |last| last.max(*end)));
+        }
+        let (Some(first), Some(mut last)) = (first, last) else {
+            println!("{}", "There are no entries on this day yet!".red());
+            return;
+        };
+        if self.date == get_today() {
+            last = last.min(*Slot::now().next());
+        }
+        let mut gaps = self.day.time_slots[..last]
+            .iter()
+            .enumerate()
+            .skip(first)
+            .filter(|(_, o)| o.is_none())
+            .fold(Vec::<(Slot, Slot)>::new(), |mut gaps, (s, _)| {
+                match gaps.last_mut() {
+                    Some((_, end)) if **end == s => *end = Slot(s + 1),
+                    _ => gaps.push((Slot(s), Slot(s + 1))),
+                }
+                gaps
+            });
+        if gaps.is_empty() {
+            println!("There are no empty spans on {}.", self.date);
+            return;
+        }
+        gaps.reverse();
+        while let Some((start, end)) = gaps.pop() {
+            println!(
+                "{} - {} is empty. (Leave {} to record it, enter a time like '{}' to split it there, '{}' to skip or '{}' to stop.)",
+                start.to_string().yellow(),
+                end.to_string().yellow(),
+                "empty".bright_blue(),
+                "10:30".bright_blue(),
+                "s".bright_blue(),
+                "q".bright_blue()
+            );
+            let candidates = ((*start + 1)..*end).map(|s| Slot(s).to_string()).collect();
+            let Some(input) = get_input_completing::<String>(candidates) else {
+                return;
+            };
+            match input.trim() {
+                "" => self.ask_about_activity(start, end),
+                "s" => {},
+                "q" => return,
+                time => match get_time(time, self.date, self.settings) {
+                    Some((date, slot)) if date == self.date && *start < *slot && *slot < *end => {
+                        self.ask_about_activity(start, slot);
+                        gaps.push((slot, end));
+                    },
+                    _ => {
+                        println!("{}", "Invalid input!".red());
+                        gaps.push((start, end));
+                    },
+                },
+            }
+        }
+    }
+
     fn ask_about_start_and_end_time(&mut self) -> Option<(Slot, Slot)> {
         println!(
-            "(Enter '{}' or a time like '{}' or just '{}'. Leave {} for 'now'.)",
+            "(Enter '{}' or a time like '{}', '{}' or '{}'. Leave {} for 'now'.)",
             "now".bright_blue(),
             "18:10".bright_blue(),
-            "18".bright_blue(),
+            "30m ago".bright_blue(),
+            "yesterday 18".bright_blue(),
             "empty".bright_blue()
         );
         println!("Start time:");
-        let start = get_input::<String>().and_then(|s| Slot::try_from(s).ok());
-        return if let Some(start) = start {
-            println!("~> {}", start.to_string().bold());
+        let start = get_input::<String>().and_then(|s| get_time(&s, self.date, self.settings));
+        if let Some((date, start)) = start {
+            println!("~> {} {}", date, start.to_string().bold());
             println!("End time:");
-            let end = get_input::<String>().and_then(|s| Slot::try_from(s).ok());
-            if let Some(end) = end {
-                println!("~> {}", end.to_string().bold());
-                if *end <= *start {
+            let end = get_input::<String>().and_then(|s| get_time(&s, date, self.settings));
+            if let Some((end_date, end)) = end {
+                println!("~> {} {}", end_date, end.to_string().bold());
+                if end_date != date {
+                    println!("{}", "Start and end time must be on the same day!".red());
+                    None
+                } else if *end <= *start {
                     println!("{}", "End time <= start time!".red());
                     None
                 } else {
+                    if date != self.date {
+                        self.switch_day(date);
+                        println!("{} {}", "Switched to".bright_blue(), date);
+                    }
                     Some((start, end))
                 }
             } else {
@@ -170,37 +400,50 @@ impl UI<'_> {
         self.ask_about_activity(Slot(start), Slot(end));
     }
 
-    fn ask_about_day(&self) -> PathBuf {
-        let time = Local::now() - Duration::hours((*DAY_START / SLOTS_PER_HOUR) as i64);
-        let default_year = time.year() as usize;
-        let default_month = time.month() as usize;
-        let default_day = time.day() as usize;
-        print!("Year [{}] ", default_year);
-        let year = get_input::<usize>().unwrap_or(default_year);
-        print!("Month [{}] ", default_month);
-        let month = get_input::<usize>().unwrap_or(default_month);
-        print!("Day [{}] ", default_day);
-        let day = get_input::<usize>().unwrap_or(default_day);
-        return self.settings.get_filename_by_date(year, month, day);
+    /// The given day (from `--date`), or else asked for. `None` if the input
+    /// ended.
+    fn ask_about_day(&self, date: Option<NaiveDate>) -> Option<NaiveDate> {
+        if let Some(date) = date {
+            return Some(date);
+        }
+        println!(
+            "Which day? (Enter e.g. '{}', '{}', '{}' or '{}'. Leave {} for today.)",
+            "yesterday".bright_blue(),
+            "last friday".bright_blue(),
+            "2025-03-24".bright_blue(),
+            "-2d".bright_blue(),
+            "empty".bright_blue()
+        );
+        loop {
+            if let Some(date) = get_day(&get_input::<String>()?) {
+                println!("~> {}", date.to_string().bold());
+                return Some(date);
+            }
+            println!("Invalid input.");
+        }
     }
 
     fn add_comment_to_last_activity(&mut self) {
         if let Some(entry) = self.day.time_slots.iter_mut().rev().filter_map(|o| o.as_mut()).next() {
             println!("Please enter a comment to add to {}.", entry);
-            entry.comment = get_input();
+            entry.comment = get_input_completing(previous_comments(self.settings, self.date));
             self.save();
         } else {
             println!("{}", "Please add a recent activity first!".red());
         }
     }
 
-    fn edit_with_text_editor(&mut self) {
+    /// Edit the recorded slots of the day, or the planned ones if `plan`.
+    fn edit_with_text_editor(&mut self, plan: bool) {
         let tmp_file = PathBuf::from_str("/tmp/time-track.tmp").unwrap();
         let mut data = String::new();
         writeln!(&mut data, "# Do not add or delete any lines in this document.").expect("write");
         writeln!(&mut data, "# Edit the activities and associated comments by changing the text.").expect("write");
         writeln!(&mut data, "# The time, activity name, and comment field (if any) must always be seperated by ' - '.").expect("write");
-        self.day.slots().for_each(|(s, e, o)| {
+        if plan {
+            writeln!(&mut data, "# This is the plan for {}.", self.date).expect("write");
+        }
+        self.day.layer(plan).iter().enumerate().for_each(|(s, o)| {
             let name = o.as_ref().map(|a| a.name.as_ref()).unwrap_or("empty");
             let comment = o.as_ref()
                 .and_then(|a| a.comment.as_ref())
@@ -209,7 +452,7 @@ impl UI<'_> {
             writeln!(
                 &mut data,
                 "{} - {}{}",
-                s,
+                Slot(s),
                 name,
                 comment
             )
@@ -224,8 +467,8 @@ impl UI<'_> {
             println!("Editor exited with non-zero exit code!");
         } else {
             let data = fs::read_to_string(tmp_file).expect("could not read file");
-            let len = self.day.time_slots.len();
-            self.day.time_slots = data
+            let len = self.day.layer(plan).len();
+            let slots = data
                 .lines()
                 .filter(|o| !o.starts_with("#"))
                 .map(|o| {
@@ -240,9 +483,9 @@ impl UI<'_> {
                     }
                     activity
                 })
-                .collect();
-            
-            if len == self.day.time_slots.len() {
+                .collect::<Vec<_>>();
+            if len == slots.len() {
+                *self.day.layer_mut(plan) = slots;
                 self.save();
             } else {
                 println!("{}", "One or more time slots were invalid!".red());
@@ -253,7 +496,6 @@ impl UI<'_> {
     fn split(&mut self, only_one_split: bool) -> bool {
         let now_or_last_entry = self.day.now_or_last_entry();
         let possible_slots = (*now_or_last_entry + 1..*Slot::now() + 1)
-            .into_iter()
             .collect::<Vec<_>>();
         if possible_slots.is_empty() {
             println!("{}", "There's nothing to split!".red());
@@ -272,7 +514,13 @@ impl UI<'_> {
             for s in possible_slots.iter() {
                 println!(" - {}", Slot(*s).to_string().bright_blue());
             }
-            get_input::<String>().and_then(|s| Slot::try_from(s).ok())
+            let candidates = std::iter::once("now".to_string())
+                .chain(possible_slots.iter().map(|s| Slot(*s).to_string()))
+                .collect();
+            get_input_completing::<String>(candidates)
+                .and_then(|s| get_time(&s, self.date, self.settings))
+                .filter(|(date, _)| *date == self.date)
+                .map(|(_, slot)| slot)
         };
         if let Some(choice) = choice {
             if possible_slots.contains(&choice) {
@@ -286,12 +534,12 @@ impl UI<'_> {
                 return false;
             }
         }
-        return false;
+        false
     }
 
     /// Print statistics for multiple days. Might skip some days if the
     /// corresponding data files do not exist.
-    fn multiday_statistics(&self, dates: impl Iterator<Item = DateTime<Local>>, print_days: bool) {
+    fn multiday_statistics(&self, dates: impl Iterator<Item = NaiveDate>, print_days: bool) {
         let mut days = Vec::new();
         let step_by = DAY_CHART_STEP_SIZE;
         if print_days {
@@ -299,7 +547,6 @@ impl UI<'_> {
                 "{}{}",
                 " ".repeat(36),
                 (0..24)
-                    .into_iter()
                     .step_by(step_by)
                     .map(|h| format!("{:<2}", (h + *DAY_START / SLOTS_PER_HOUR) % 24))
                     .join("  ")
@@ -308,41 +555,31 @@ impl UI<'_> {
         }
         let mut print = false;
         for date in dates {
-            let time = date.borrow();
-            let file = self.settings.get_filename_by_date(
-                time.year() as usize,
-                time.month() as usize,
-                time.day() as usize,
-            );
+            let file = self.settings.get_filename_by_day(date);
             if file.exists() {
                 print = true;
-                let day: Day = serde_json::from_str(
-                    fs::read_to_string(file)
-                        .expect("could not read file")
-                        .as_str(),
-                )
-                .unwrap();
+                let day = Day::read(&file);
                 if print_days && print {
                     println!(
                         "{}, {:02}.{:02}.: {:4.1} hrs. {}",
-                        time.weekday().to_string(),
-                        time.day(),
-                        time.month(),
+                        date.weekday(),
+                        date.day(),
+                        date.month(),
                         day.hours_productive(),
-                        day.activity_string(&self.settings, step_by)
+                        day.activity_string(self.settings, step_by)
                     );
                 }
-                days.push(day);
+                days.push((date, day));
             } else if print {
                 println!(
                     "{}, {:02}.{:02}.:  no data",
-                    time.weekday().to_string(),
-                    time.day(),
-                    time.month()
+                    date.weekday(),
+                    date.day(),
+                    date.month()
                 );
             }
         }
-        let hours: f32 = days.iter().map(|d| d.hours_productive()).sum();
+        let hours: f32 = days.iter().map(|(_, d)| d.hours_productive()).sum();
         let hours_by_activity: HashMap<Activity, f32> = self
             .settings
             .activities
@@ -350,18 +587,7 @@ impl UI<'_> {
             .map(|activity| {
                 (
                     activity.clone(),
-                    days.iter()
-                        .map(|d| {
-                            d.time_slots
-                                .iter()
-                                .filter(|activity_at_time| {
-                                    activity_at_time.is_some()
-                                        && activity_at_time.as_ref().unwrap() == activity
-                                })
-                                .count() as f32
-                                / SLOTS_PER_HOUR as f32
-                        })
-                        .sum(),
+                    days.iter().map(|(_, d)| d.hours_of(activity)).sum(),
                 )
             })
             .collect();
@@ -387,23 +613,28 @@ impl UI<'_> {
                     print!("{:40}", str);
                 }
             });
+        if let Some(compliance) = &self.settings.compliance {
+            compliance::print_violations(&compliance.check(self.settings, &days));
+        }
     }
 
-    fn save(&self) {
+    fn save(&mut self) {
         println!("{}", "Saved!".bright_blue());
         self.day.write(&self.file);
     }
 }
 
-fn get_or_create_settings() -> Option<Settings> {
-    let mut settings_file: PathBuf;
+fn get_or_create_settings(config: Option<PathBuf>) -> Option<Settings> {
+    let settings_file: PathBuf;
 
-    if let Ok(path) = std::env::var(CONFIG_OVERRIDE_ENV_VAR) {
+    if let Some(path) = config {
+        settings_file = path
+    } else if let Ok(path) = std::env::var(CONFIG_OVERRIDE_ENV_VAR) {
         settings_file = PathBuf::from(path)
     } else {
         settings_file = get_base_dirs()
             .config_dir()
-            .join(CONFIG_FILENAME.to_string());
+            .join(CONFIG_FILENAME);
     }
 
     if !settings_file.exists() {
@@ -412,11 +643,17 @@ fn get_or_create_settings() -> Option<Settings> {
             name: "Example".to_string(),
             productive: false,
             comment: None,
+            tags: vec![],
+            shortcut: None,
+            routine: false,
         });
         settings.activities.push(Activity {
             name: "Second Example".to_string(),
             productive: true,
             comment: None,
+            tags: vec![],
+            shortcut: None,
+            routine: false,
         });
 
         let author = Command::new(&settings.git)
@@ -444,250 +681,441 @@ fn get_or_create_settings() -> Option<Settings> {
             .expect("read settings")
             .as_str(),
     )
-    .expect(&format!("parse settings {:?}", &settings_file)))
+    .unwrap_or_else(|e| panic!("parse settings {:?}: {}", &settings_file, e)))
 }
 
 fn main() {
-    let settings = get_or_create_settings();
+    let cli = Cli::parse_from(cli::expand_shorthand(std::env::args()));
+    let settings = get_or_create_settings(cli.config.clone());
     if settings.is_none() {
         return;
     }
-    let settings = settings.unwrap();
-
-    let file = PathBuf::from(settings.get_filename_today());
-    let day = if file.exists() {
-        serde_json::from_str(
-            fs::read_to_string(file.clone())
-                .expect("could not read file")
-                .as_str(),
-        )
-        .unwrap()
-    } else {
-        Day::default()
-    };
-    assert_eq!(day.time_slots.len(), DAY_SLOTS, "Loaded day file {} is invalid.", file.display());
+    let mut settings = settings.unwrap();
+    if let Some(data_dir) = &cli.data_dir {
+        settings.data_dir = data_dir.clone();
+    }
+    let settings = settings;
+    prompt::init(&settings);
+
+    let date = get_today();
+    let file = settings.get_filename_by_day(date);
     let mut ui = UI {
-        day,
-        file: file.clone(),
+        day: Day::load(&settings, date),
+        date,
+        file,
         settings: &settings,
     };
-    if let Some(arg) = std::env::args().nth(1) {
-        let index = arg.chars().find_position(|c| c.is_alphabetic()).map(|(i, _)| i).unwrap_or(0);
-        let (count_str, arg) = arg.split_at(index);
-        let count = count_str.parse::<usize>().unwrap_or(1);
-        match arg {
-            "h" | "help" => {
-                println!("Commands:");
-                println!("\tactivity (a): Enter an activity for a specific time span.");
-                println!("\tcomment (c): Add comment to last activity.");
-                println!("\tday (d): Print statistics for a specific day.");
-                println!("\tyesterday (yd): Print statistics for yesterday.");
-                println!("\tlastday (ld): Print statistics for the last day.");
-                println!("\tedit (e): Edit activities for a specific day in text editor.");
-                println!("\tedittoday (ed): Edit activities for today in text editor.");
-                println!("\tpath (p): Print today's data file path.");
-                println!("\tsplit (s): Split the time since the last recorded activity in two (three, ...)");
-                println!("\ttoday (t): Print statistics for today.");
-                println!("\tuntil (u): Like split, but only enter the first activity.");
-                println!("\tweek (w): Print statistics for last seven days.");
-                println!("\tyear (y): Print statistics for last year.");
-                println!("\t{}", "Tip: many commands work with a prefixed count, e.g.: 3s, 3d, 3w, ...".bright_blue());
-                println!();
-                println!("Danger zone:");
-                println!("\tclear: Delete today's file.");
-                println!();
-                println!("Current data file: {:?}", &file);
-                let settings_file = get_base_dirs()
-                    .config_dir()
-                    .join(CONFIG_FILENAME.to_string());
-                println!("Config file: {:?}", &settings_file);
-                println!("Set {} to override config file path.", CONFIG_OVERRIDE_ENV_VAR);
-            },
-            "p" | "path" => {
-                println!("{}", file.display());
-            }
-            "a" | "activity" => {
-                for _ in 0..count {
-                    ui.print_current_slot_info();
-                    if let Some((start, end)) = ui.ask_about_start_and_end_time() {
-                        ui.ask_about_activity(start, end);
-                    }
+    exit_on_error(run(cli, &mut ui));
+}
+
+/// Run a single command on the day of `ui`, for the command line and for
+/// each line of the shell.
+fn run(cli: Cli, ui: &mut UI) -> Result<(), String> {
+    let settings = ui.settings;
+    let command = if let Some(command) = cli.command {
+        command
+    } else {
+        ui.require_today("time-tracker")?;
+        ui.print_current_slot_info();
+        ui.ask_about_activity_now();
+        return Ok(());
+    };
+    match command {
+        Commands::Export { format: ExportFormat::Org, range } => {
+            let days = range
+                .dates(1)
+                .into_iter()
+                .map(|date| (date, settings.get_filename_by_day(date)))
+                .filter(|(_, file)| file.exists())
+                .map(|(date, file)| (date, Day::read(&file)))
+                .collect_vec();
+            print!("{}", org::export(settings, &days));
+        },
+        Commands::Import { file: path } => {
+            let text = fs::read_to_string(&path).expect("could not read file");
+            for (date, day) in org::import(settings, &text).iter().sorted_by_key(|(date, _)| **date) {
+                let file = settings.get_filename_by_day(*date);
+                println!("{} {:?}", "Importing".bright_blue(), file);
+                day.write(&file);
+            }
+        },
+        Commands::Balance => {
+            if let Some(balance) = &settings.balance {
+                balance::print_balance(settings, balance, get_today());
+            } else {
+                println!("{}", "Please add a [balance] section with a start date to the config file!".red());
+            }
+        },
+        Commands::Chart { svg: prefix, png, range } => {
+            let report = report::Report::load(settings, range.dates(1));
+            let charts = [
+                ("activities", chart::activity_bars(settings, &report)),
+                ("productive", chart::productive_line(&report)),
+                ("heatmap", chart::heatmap(settings, &report)),
+            ];
+            for (name, svg) in charts.iter() {
+                let path = PathBuf::from(format!("{}-{}.svg", prefix, name));
+                fs::write(&path, svg).expect("write");
+                println!("{} {:?}", "Saved chart to".bright_blue(), path);
+                if png && !chart::convert_to_png(settings, &path) {
+                    println!("{}{}", "Could not convert to PNG with ".red(), settings.svg_converter);
                 }
-            },
-            "d" | "day" => {
-                if count != 1 {
-                    ui.multiday_statistics(
-                        (0..count as i64).rev().map(|i| Local::now() - Duration::days(i)),
-                        true,
-                    );
-                } else {
-                    let file = ui.ask_about_day();
-                    println!("Loading file {:?}", file);
-                    let day: Day = serde_json::from_str(
-                        fs::read_to_string(file)
-                            .expect("could not read file")
-                            .as_str(),
-                    )
-                        .unwrap();
-                    day.print_stats(false, true);
+            }
+        },
+        Commands::Compliance { range } => {
+            let compliance = if let Some(compliance) = &settings.compliance {
+                compliance
+            } else {
+                println!("{}", "Please add a [compliance] section to the config file!".red());
+                return Ok(());
+            };
+            let days = range
+                .dates(7)
+                .into_iter()
+                .map(|date| (date, settings.get_filename_by_day(date)))
+                .filter(|(_, file)| file.exists())
+                .map(|(date, file)| (date, Day::read(&file)))
+                .collect_vec();
+            let violations = compliance.check(settings, &days);
+            if violations.is_empty() {
+                println!("{}", "No violations.".green());
+            } else {
+                compliance::print_violations(&violations);
+            }
+        },
+        Commands::Report { markdown, html, range } => {
+            if let Some(period) = markdown {
+                let dates = match period {
+                    ReportPeriod::Week => range.dates(7),
+                    ReportPeriod::Month => range.months(),
+                };
+                print!("{}", report::Report::load(settings, dates).markdown());
+            } else if let Some(path) = html {
+                let report = report::Report::load(settings, range.dates(1));
+                fs::write(&path, report.html()).expect("write");
+                println!("{} {:?}", "Saved report to".bright_blue(), path);
+            }
+        },
+        Commands::Invoice { client, out, dry_run, format, range } => {
+            let client = settings.get_client(&client).ok_or_else(|| {
+                format!(
+                    "Unknown client: {} (configured: {})",
+                    client,
+                    settings.clients.iter().map(|c| c.name.as_str()).join(", ")
+                )
+            })?;
+            let invoices_file = settings.data_dir.join(billing::INVOICES_FILENAME);
+            let mut records = billing::read_records(&invoices_file);
+            let (dates, invoiced) = billing::partition_invoiced(&records, client, range.dates(1));
+            if !invoiced.is_empty() {
+                println!(
+                    "{}: Skipping {} day(s) that were already invoiced.",
+                    "Warning".bright_yellow(),
+                    invoiced.len()
+                );
+            }
+            let days = dates
+                .iter()
+                .map(|date| (*date, settings.get_filename_by_day(*date)))
+                .filter(|(_, file)| file.exists())
+                .map(|(date, file)| (date, Day::read(&file)))
+                .collect_vec();
+            let invoice = billing::Invoice::new(
+                billing::next_number(&records, get_today()),
+                client,
+                *dates.first().unwrap_or(&get_today()),
+                *dates.last().unwrap_or(&get_today()),
+                &days,
+            );
+            if invoice.items.is_empty() {
+                println!("{}", "Nothing to invoice.".red());
+                return Ok(());
+            }
+            let data = match format {
+                InvoiceFormat::Md => invoice.markdown(),
+                InvoiceFormat::Html => invoice.html(),
+                InvoiceFormat::Txt => invoice.text(),
+            };
+            let out = out
+                .unwrap_or_else(|| PathBuf::from(format!("invoice-{}.{}", invoice.number, format.extension())));
+            fs::write(&out, data).expect("write");
+            println!(
+                "{} {:?} ({:.2} h, {:.2} {})",
+                "Saved invoice to".bright_blue(),
+                out,
+                invoice.hours(),
+                invoice.total(),
+                client.currency
+            );
+            if !dry_run {
+                records.push(billing::InvoiceRecord {
+                    number: invoice.number.clone(),
+                    client: client.name.clone(),
+                    from: invoice.from,
+                    to: invoice.last_date(),
+                    created: get_today(),
+                });
+                billing::write_records(&invoices_file, &records);
+            }
+        },
+        Commands::Path => {
+            if let Some(date) = cli.date {
+                println!("{}", settings.get_filename_by_day(date).display());
+            } else {
+                println!("{}", ui.file.display());
+            }
+        },
+        Commands::Add { activity, range, comment, tag } => {
+            if let Some(date) = cli.date {
+                ui.switch_day(date);
+            }
+            record::add(ui, &activity, &range, comment, tag)?;
+        },
+        Commands::Activity { count } => {
+            for _ in 0..count {
+                ui.print_current_slot_info();
+                if let Some((start, end)) = ui.ask_about_start_and_end_time() {
+                    ui.ask_about_activity(start, end);
                 }
-            },
-            "yd" | "yesterday" => {
-                let time = Local::now() - Duration::hours((*DAY_START / SLOTS_PER_HOUR) as i64) - Duration::days(1);
-                let file = settings.get_filename_by_date(time.year() as usize, time.month() as usize, time.day() as usize);
+            }
+        },
+        Commands::Day { range } => {
+            if range.count != 1 || range.is_given() {
+                ui.multiday_statistics(range.dates(1).into_iter(), true);
+            } else {
+                let date = if let Some(date) = ui.ask_about_day(cli.date) {
+                    date
+                } else {
+                    return Ok(());
+                };
+                let file = settings.get_filename_by_day(date);
                 println!("Loading file {:?}", file);
-                let day: Day = serde_json::from_str(
-                    fs::read_to_string(file)
-                        .expect("could not read file")
-                        .as_str(),
-                )
-                    .unwrap();
+                let day = Day::read(&file);
                 day.print_stats(false, true);
-            },
-            "ld" | "lastday" => {
-                let time = Local::now() - Duration::hours((*DAY_START / SLOTS_PER_HOUR) as i64) - Duration::days(1);
-                let year = time.year() as usize;
-                let month = time.month() as usize;
-                let mut day = time.day() as usize;
-
-                let mut file = None;
-                while day > 1 {
-                    let file_path = settings.get_filename_by_date(year, month, day);
-                    if file_path.exists() {
-                        file = Some(file_path);
-                        break;
-                    }
-                    day -= 1;
+            }
+        },
+        Commands::Yesterday => {
+            let time = cli.date.unwrap_or(ui.date) - Duration::days(1);
+            let file = settings.get_filename_by_day(time);
+            if !file.exists() {
+                return Err(format!("Nothing was recorded on {}.", time));
+            }
+            println!("Loading file {:?}", file);
+            let day = Day::read(&file);
+            day.print_stats(false, true);
+        },
+        Commands::Lastday => {
+            let time = cli.date.unwrap_or(ui.date) - Duration::days(1);
+            let year = time.year() as usize;
+            let month = time.month() as usize;
+            let mut day = time.day() as usize;
+
+            let mut file = None;
+            while day > 1 {
+                let file_path = settings.get_filename_by_date(year, month, day);
+                if file_path.exists() {
+                    file = Some(file_path);
+                    break;
                 }
+                day -= 1;
+            }
 
-                if let Some(file_path) = file {
-                    println!("Loading file {:?}", file_path);
-                    println!("Last day: {}-{}-{}", year, month, day);
-                    let day: Day = serde_json::from_str(
-                        fs::read_to_string(file_path)
-                            .expect("could not read file")
-                            .as_str(),
-                    )
-                        .unwrap();
-                    day.print_stats(false, true);
-                } else {
-                    println!("{}", "No data file found in this month.".red());
+            if let Some(file_path) = file {
+                println!("Loading file {:?}", file_path);
+                println!("Last day: {}-{}-{}", year, month, day);
+                let day = Day::read(&file_path);
+                day.print_stats(false, true);
+            } else {
+                println!("{}", "No data file found in this month.".red());
+            }
+        },
+        Commands::Today => {
+            ui.require_today("today")?;
+            ui.print_current_slot_info();
+            ui.day.print_stats(true, true);
+            if let Some(compliance) = &settings.compliance {
+                let today = ui.date;
+                let mut violations = compliance.check_day(settings, today, &ui.day);
+                let yesterday = today.pred_opt().unwrap();
+                let file = settings.get_filename_by_day(yesterday);
+                if file.exists() {
+                    violations.extend(compliance.check_rest(
+                        settings,
+                        (yesterday, &Day::read(&file)),
+                        (today, &ui.day),
+                    ));
                 }
-            },
-            "t" | "today" => {
-                ui.print_current_slot_info();
-                ui.day.print_stats(true, true);
-            },
-            "w" | "week" => {
-                ui.multiday_statistics(
-                    (0..(7 * count) as i64).rev().map(|i| Local::now() - Duration::days(i)),
-                    true,
-                );
-            },
-            "y" | "year" => {
-                ui.multiday_statistics(
-                    (0..(365 * count) as i64).rev().map(|i| Local::now() - Duration::days(i)),
-                    false,
-                );
-            },
-            "e" | "edit" => {
-                let file = ui.ask_about_day();
-                println!("Loading file {:?}", file);
-                let day: Day = serde_json::from_str(
-                    fs::read_to_string(file.clone())
-                        .expect("could not read file")
-                        .as_str(),
-                )
-                .unwrap();
-                ui = UI {
-                    day,
-                    file,
-                    settings: &settings,
-                };
-                ui.edit_with_text_editor();
-            },
-            "ed" | "edittoday" => {
-                ui.edit_with_text_editor();
-            },
-            "s" | "split" => {
-                ui.print_current_slot_info();
-                let mut i = count;
-                while i > 2 {
-                    if !ui.split(true) {
-                        return;
-                    }
-                    i -= 1;
+                compliance::print_violations(&violations);
+            }
+        },
+        Commands::Week { range } => {
+            ui.multiday_statistics(range.dates(7).into_iter(), true);
+        },
+        Commands::Year { range } => {
+            ui.multiday_statistics(range.dates(365).into_iter(), false);
+        },
+        Commands::Edit { plan } => {
+            let date = if let Some(date) = ui.ask_about_day(cli.date) {
+                date
+            } else {
+                return Ok(());
+            };
+            let file = settings.get_filename_by_day(date);
+            println!("Loading file {:?}", file);
+            *ui = UI {
+                day: Day::load(settings, date),
+                date,
+                file,
+                settings,
+            };
+            ui.edit_with_text_editor(plan);
+        },
+        Commands::Edittoday { plan } => {
+            ui.edit_with_text_editor(plan);
+        },
+        Commands::Split { args, .. } if !args.is_empty() => {
+            record::split(ui, &args)?;
+        },
+        Commands::Split { count, .. } => {
+            ui.require_today("split")?;
+            ui.print_current_slot_info();
+            let mut i = count;
+            while i > 2 {
+                if !ui.split(true) {
+                    return Ok(());
                 }
-                ui.split(false);
-            },
-            "u" | "until" => {
-                ui.print_current_slot_info();
-                ui.split(true);
-            },
-            "c" | "comment" => {
-                ui.print_current_slot_info();
-                ui.add_comment_to_last_activity();
-            },
-            "clear" => {
-                if file.exists() {
-                    println!("{} Are you sure you want to delete today's file? (y/N)", "DANGER:".red());
-                    if let Some("y") = get_input::<String>().as_ref().map(|s| s.as_str()) {
-                        fs::remove_file(&file).expect("remove file");
-                        println!("{} {:?}.", "Deleted".bright_blue(), file);
-                    }
-                } else {
-                    println!("No file to delete.");
+                i -= 1;
+            }
+            ui.split(false);
+        },
+        Commands::Until => {
+            ui.require_today("until")?;
+            ui.print_current_slot_info();
+            ui.split(true);
+        },
+        Commands::Comment { at, text } if !text.is_empty() => {
+            if let Some(date) = cli.date {
+                ui.switch_day(date);
+            }
+            record::comment(ui, at, text.join(" "))?;
+        },
+        Commands::Comment { .. } => {
+            ui.print_current_slot_info();
+            ui.add_comment_to_last_activity();
+        },
+        Commands::Clear => {
+            let file = settings.get_filename_today();
+            if file.exists() {
+                println!("{} Are you sure you want to delete today's file? (y/N)", "DANGER:".red());
+                if let Some("y") = get_input::<String>().as_deref() {
+                    fs::remove_file(&file).expect("remove file");
+                    println!("{} {:?}.", "Deleted".bright_blue(), file);
                 }
-            },
-            "json" => {
-                let day_maps = (0..365).rev()
-                    .map(|i| Local::now() - Duration::days(i))
-                    .filter_map(|time| {
-                        let file = settings.get_filename_by_date(
-                            time.year() as usize,
-                            time.month() as usize,
-                            time.day() as usize,
-                        );
-                        if file.exists() {
-                            Some(serde_json::from_str(
-                                fs::read_to_string(file)
-                                    .expect("read file")
-                                    .as_str()
-                            ).expect("deserialize"))
-                        } else {
-                            None
-                        }
-                    })
-                    .map(|d: Day| {
-                        d.time_slots.iter()
-                            .fold(HashMap::default(), |mut map: HashMap<Activity, usize>, slot| {
-                                if let Some(activity) = slot {
-                                    *map.entry(activity.clone())
-                                        .or_insert(0) += 1;
-                                }
-                                map
-                            })
-                    })
-                    .collect_vec();
-                println!("{{");
-                for activity in &settings.activities {
-                    print!("\t\"{}\": [\n\t\t", activity.name);
-                    for day in day_maps.iter() {
-                        let &half_hours = day.get(activity).unwrap_or(&0);
-                        print!("{}, ", half_hours as f32 / SLOTS_PER_HOUR as f32);
-                    }
-                    println!("\n\t],");
+            } else {
+                println!("No file to delete.");
+            }
+        },
+        Commands::Json { range } => {
+            let day_maps = range
+                .dates(365)
+                .into_iter()
+                .map(|date| settings.get_filename_by_day(date))
+                .filter(|file| file.exists())
+                .map(|file| Day::read(&file))
+                .map(|d: Day| {
+                    d.time_slots.iter()
+                        .fold(HashMap::default(), |mut map: HashMap<Activity, usize>, slot| {
+                            if let Some(activity) = slot {
+                                *map.entry(activity.clone())
+                                    .or_insert(0) += 1;
+                            }
+                            map
+                        })
+                })
+                .collect_vec();
+            println!("{{");
+            for activity in &settings.activities {
+                print!("\t\"{}\": [\n\t\t", activity.name);
+                for day in day_maps.iter() {
+                    let &half_hours = day.get(activity).unwrap_or(&0);
+                    print!("{}, ", half_hours as f32 / SLOTS_PER_HOUR as f32);
                 }
-                println!("}}");
+                println!("\n\t],");
             }
-            arg => {
-                println!("{}{}", "Unknown command: ".red(), arg);
-                ui.print_current_slot_info();
-                ui.ask_about_activity_now()
-            },
-        }
-    } else {
-        ui.print_current_slot_info();
-        ui.ask_about_activity_now();
+            println!("}}");
+        },
+        Commands::Completions { shell } => {
+            cli::print_completions(shell, settings);
+        },
+        Commands::Tui { plan } => {
+            tui::run(settings, cli.date.unwrap_or(ui.date), plan).expect("terminal");
+        },
+        Commands::Start { activity, comment } => {
+            timer::start(settings, &activity, Some(comment.join(" ")).filter(|c| !c.is_empty()))?;
+        },
+        Commands::Stop => {
+            timer::stop(settings)?;
+        },
+        Commands::Switch { activity, comment } => {
+            timer::switch(settings, &activity, Some(comment.join(" ")).filter(|c| !c.is_empty()))?;
+        },
+        Commands::Focus { activity, durations, cycles, break_activity } => {
+            focus::run(settings, &activity, durations, cycles, break_activity)?;
+        },
+        Commands::Daemon { print } => {
+            let reminder = settings.reminder.clone().unwrap_or_default();
+            if print {
+                reminder::run(settings, &reminder, &mut reminder::PrintNotifier);
+            } else {
+                let mut notifier = reminder::CommandNotifier { command: reminder.notifier.clone() };
+                reminder::run(settings, &reminder, &mut notifier);
+            }
+        },
+        Commands::Status { format } => {
+            ui.require_today("status")?;
+            let status = status::Status::new(settings, ui.date, &ui.day, Local::now().naive_local());
+            match format {
+                StatusFormat::Plain => println!("{}", status.plain()),
+                StatusFormat::Tmux => println!("{}", status.tmux()),
+                StatusFormat::WaybarJson => println!("{}", status.waybar()),
+            }
+        },
+        Commands::Shell => {
+            shell::run(ui)?;
+        },
+        Commands::SaveTemplate { name, range } => {
+            if let Some(date) = cli.date {
+                ui.switch_day(date);
+            }
+            template::save(ui, &name, range.as_deref())?;
+        },
+        Commands::ApplyTemplate { name, yes } => {
+            if let Some(date) = cli.date {
+                ui.switch_day(date);
+            }
+            template::apply(ui, &name, yes)?;
+        },
+        Commands::Review { range } => {
+            let dates = if range.count != 1 || range.is_given() {
+                range.dates(1)
+            } else {
+                vec![cli.date.unwrap_or(ui.date)]
+            };
+            let days = dates
+                .into_iter()
+                .map(|date| (date, settings.get_filename_by_day(date)))
+                .filter(|(_, file)| file.exists())
+                .map(|(date, file)| (date, Day::read(&file)))
+                .collect_vec();
+            Review::new(&days).print();
+        },
+        Commands::Fill { hours } => {
+            if let Some(date) = cli.date {
+                ui.switch_day(date);
+            }
+            let hours = hours.map(|range| record::parse_range(ui, &range)).transpose()?;
+            ui.fill_gaps(hours);
+        },
     }
+    Ok(())
 }
@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use colored::Colorize;
+use itertools::Itertools;
+use crate::activity::Activity;
+use crate::day::{Day, Slot};
+use crate::settings::Settings;
+use crate::SLOTS_PER_HOUR;
+
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %a %H:%M";
+
+/// Render the given days as an org-mode document with one heading per
+/// activity and one `CLOCK:` line per recorded range. Comments become list
+/// items below their clock line, which is also where `import` looks for them.
+pub fn export(settings: &Settings, days: &[(NaiveDate, Day)]) -> String {
+    let clocks = days
+        .iter()
+        .flat_map(|(date, day)| {
+            day.entries()
+                .into_iter()
+                .map(move |(s, e, act)| (act, s.to_datetime(*date), e.to_datetime(*date)))
+        })
+        .into_group_map_by(|(act, _, _)| act.name.clone());
+
+    let mut data = String::new();
+    for (name, clocks) in clocks.iter().sorted_by_key(|(name, _)| {
+        settings
+            .activities
+            .iter()
+            .position(|a| &a.name == *name)
+            .unwrap_or(settings.activities.len())
+    }) {
+        writeln!(&mut data, "* {}", name).expect("write");
+        writeln!(&mut data, "  :LOGBOOK:").expect("write");
+        for (act, start, end) in clocks {
+            let minutes = (*end - *start).num_minutes();
+            writeln!(
+                &mut data,
+                "  CLOCK: [{}]--[{}] => {:2}:{:02}",
+                start.format(TIMESTAMP_FORMAT),
+                end.format(TIMESTAMP_FORMAT),
+                minutes / 60,
+                minutes % 60
+            )
+            .expect("write");
+            if let Some(comment) = &act.comment {
+                writeln!(&mut data, "  - {}", comment).expect("write");
+            }
+        }
+        writeln!(&mut data, "  :END:").expect("write");
+    }
+    data
+}
+
+fn parse_timestamp(text: &str) -> Option<NaiveDateTime> {
+    let mut parts = text.trim_matches(|c| c == '[' || c == ']').split_whitespace();
+    let date = NaiveDate::parse_from_str(parts.next()?, "%Y-%m-%d").ok()?;
+    // The weekday is optional and only there for humans.
+    let time = parts.last()?;
+    let time = NaiveTime::parse_from_str(time, "%H:%M").ok()?;
+    Some(date.and_time(time))
+}
+
+fn parse_clock(line: &str) -> Option<(NaiveDateTime, NaiveDateTime)> {
+    let range = line.strip_prefix("CLOCK:")?.split("=>").next()?.trim();
+    let (start, end) = range.split_once("--")?;
+    Some((parse_timestamp(start)?, parse_timestamp(end)?))
+}
+
+/// Strip TODO keywords, priorities and tags from a heading so that only the
+/// title remains.
+fn heading_title(heading: &str) -> &str {
+    let mut title = heading.trim_start_matches('*').trim();
+    for keyword in ["TODO", "NEXT", "DONE"] {
+        if let Some(rest) = title.strip_prefix(keyword).filter(|r| r.starts_with(' ')) {
+            title = rest.trim_start();
+        }
+    }
+    if title.starts_with("[#") {
+        title = title.split_once(']').map_or("", |(_, rest)| rest).trim_start();
+    }
+    if title.ends_with(':') {
+        if let Some((rest, _tags)) = title.trim_end_matches(':').rsplit_once(' ') {
+            title = rest.trim_end();
+        }
+    }
+    title
+}
+
+/// Read all `CLOCK:` entries from an org document into the days they belong
+/// to. Clocked time is rounded to slots; a slot is filled if the clock covers
+/// its start. Every heading is mapped onto the activity with the same
+/// (case-insensitive) name; clocks below unknown headings are skipped.
+/// Existing days are loaded from disk so that only the clocked slots change.
+pub fn import(settings: &Settings, text: &str) -> HashMap<NaiveDate, Day> {
+    let minutes_per_slot = (60 / SLOTS_PER_HOUR) as i64;
+    let mut days: HashMap<NaiveDate, Day> = HashMap::new();
+    let mut activity: Option<Activity> = None;
+    // Slot of the last clock, so that a list item below it becomes its comment.
+    let mut last_clock: Option<(NaiveDate, Slot)> = None;
+
+    for line in text.lines().map(str::trim) {
+        if line.starts_with('*') {
+            let title = heading_title(line);
+            activity = settings
+                .activities
+                .iter()
+                .find(|a| a.name.eq_ignore_ascii_case(title))
+                .cloned();
+            if activity.is_none() {
+                println!("{}: Ignoring heading with unrecognized activity name '{}'!", "Warning".bright_yellow(), title);
+            }
+            last_clock = None;
+        } else if line.starts_with("CLOCK:") {
+            last_clock = None;
+            let act = match &activity {
+                Some(act) => act,
+                None => continue,
+            };
+            let (start, end) = match parse_clock(line) {
+                Some(clock) => clock,
+                None => {
+                    println!("{}: Ignoring malformed clock line '{}'!", "Warning".bright_yellow(), line);
+                    continue;
+                },
+            };
+            let (start_date, start_slot) = Slot::from_datetime(start);
+            let mut time = start_slot.to_datetime(start_date);
+            while time < end {
+                let (date, slot) = Slot::from_datetime(time);
+                let day = days.entry(date).or_insert_with(|| {
+                    let file = settings.get_filename_by_day(date);
+                    if file.exists() {
+                        Day::read(&file)
+                    } else {
+                        Day::default()
+                    }
+                });
+                day.time_slots[*slot] = Some(act.clone());
+                last_clock.get_or_insert((date, slot));
+                time += Duration::minutes(minutes_per_slot);
+            }
+        } else if let Some(comment) = line.strip_prefix("- ") {
+            if let Some((date, slot)) = last_clock.take() {
+                if let Some(act) = days.get_mut(&date).and_then(|d| d.time_slots[*slot].as_mut()) {
+                    act.comment = Some(comment.to_string());
+                }
+            }
+        }
+    }
+    days
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(text: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+    }
+
+    fn name_at(day: &Day, hour: usize, minute: usize) -> Option<&str> {
+        day.time_slots[*Slot::from_time(hour, minute)].as_ref().map(|a| a.name.as_str())
+    }
+
+    #[test]
+    fn parses_clock_lines() {
+        let (start, end) = parse_clock("CLOCK: [2025-03-24 Mon 09:00]--[2025-03-24 Mon 10:30] =>  1:30").unwrap();
+        assert_eq!(start, date("2025-03-24").and_hms_opt(9, 0, 0).unwrap());
+        assert_eq!(end, date("2025-03-24").and_hms_opt(10, 30, 0).unwrap());
+        // The weekday and the duration are optional.
+        let (start, _) = parse_clock("CLOCK: [2025-03-24 09:15]--[2025-03-24 10:00]").unwrap();
+        assert_eq!(start, date("2025-03-24").and_hms_opt(9, 15, 0).unwrap());
+        assert!(parse_clock("CLOCK: [2025-03-24 Mon 09:00]").is_none());
+        assert!(parse_clock("CLOCK: [yesterday]--[today]").is_none());
+    }
+
+    #[test]
+    fn strips_keywords_priorities_and_tags_from_headings() {
+        assert_eq!(heading_title("* Work"), "Work");
+        assert_eq!(heading_title("** TODO [#A] Learn Rust   :study:rust:"), "Learn Rust");
+        assert_eq!(heading_title("* DONE Break"), "Break");
+        assert_eq!(heading_title("* TODOs"), "TODOs");
+    }
+
+    #[test]
+    fn imports_clocks_with_the_comment_on_the_first_slot() {
+        let settings = Settings::with_activities(&["Work", "Learn"]);
+        let text = "* TODO work\n\
+                    :LOGBOOK:\n\
+                    CLOCK: [2025-03-24 Mon 09:00]--[2025-03-24 Mon 10:30] =>  1:30\n\
+                    - code review\n\
+                    :END:\n\
+                    * Unknown\n\
+                    CLOCK: [2025-03-24 Mon 11:00]--[2025-03-24 Mon 12:00] =>  1:00\n";
+        let days = import(&settings, text);
+        let day = &days[&date("2025-03-24")];
+        assert_eq!(name_at(day, 8, 45), None);
+        assert_eq!(name_at(day, 9, 0), Some("Work"));
+        assert_eq!(name_at(day, 10, 15), Some("Work"));
+        assert_eq!(name_at(day, 10, 30), None);
+        assert_eq!(name_at(day, 11, 0), None);
+        let comments = day.time_slots.iter().flatten().filter_map(|a| a.comment.as_deref()).collect::<Vec<_>>();
+        assert_eq!(comments, ["code review"]);
+        assert_eq!(day.time_slots[*Slot::from_time(9, 0)].as_ref().unwrap().comment.as_deref(), Some("code review"));
+    }
+
+    #[test]
+    fn imports_ranges_past_midnight_into_the_day_they_started() {
+        let settings = Settings::with_activities(&["Work"]);
+        let text = "* Work\n\
+                    CLOCK: [2025-03-24 Mon 23:00]--[2025-03-25 Tue 01:00] =>  2:00\n\
+                    CLOCK: [2025-03-25 Tue 03:30]--[2025-03-25 Tue 04:30] =>  1:00\n";
+        let days = import(&settings, text);
+        assert_eq!(days.len(), 2);
+        let first = &days[&date("2025-03-24")];
+        assert_eq!(name_at(first, 23, 0), Some("Work"));
+        assert_eq!(name_at(first, 0, 45), Some("Work"));
+        assert_eq!(name_at(first, 1, 0), None);
+        // The day only ends at DAY_START.
+        assert_eq!(name_at(first, 3, 45), Some("Work"));
+        let second = &days[&date("2025-03-25")];
+        assert_eq!(name_at(second, 4, 15), Some("Work"));
+        assert_eq!(name_at(second, 4, 30), None);
+    }
+
+    #[test]
+    fn export_can_be_imported_again() {
+        let settings = Settings::with_activities(&["Work", "Learn"]);
+        let mut day = Day::default();
+        for hour in [9, 10] {
+            for minute in [0, 15, 30, 45] {
+                day.time_slots[*Slot::from_time(hour, minute)] = Some(settings.activities[0].clone());
+            }
+        }
+        day.time_slots[*Slot::from_time(23, 45)] = Some(settings.activities[1].clone());
+        day.time_slots[*Slot::from_time(9, 0)].as_mut().unwrap().comment = Some("standup".to_string());
+        let text = export(&settings, &[(date("2025-03-24"), day.clone())]);
+        assert!(text.contains("CLOCK: [2025-03-24 Mon 09:00]--[2025-03-24 Mon 11:00] =>  2:00\n  - standup\n"));
+        let days = import(&settings, &text);
+        assert_eq!(days[&date("2025-03-24")].time_slots, day.time_slots);
+    }
+}
@@ -0,0 +1,269 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Weekday};
+use crate::day::{Rounding, Slot};
+use crate::{DAY_START, SLOTS_PER_HOUR};
+
+/// The parts of a date/time expression, before they are resolved against
+/// the current time.
+#[derive(Default)]
+struct Expression {
+    date: Option<NaiveDate>,
+    time: Option<(u32, u32)>,
+    days: i64,
+    minutes: i64,
+}
+
+fn parse_weekday(text: &str) -> Option<Weekday> {
+    let weekdays = [
+        ("monday", Weekday::Mon),
+        ("tuesday", Weekday::Tue),
+        ("wednesday", Weekday::Wed),
+        ("thursday", Weekday::Thu),
+        ("friday", Weekday::Fri),
+        ("saturday", Weekday::Sat),
+        ("sunday", Weekday::Sun),
+    ];
+    weekdays
+        .iter()
+        .find(|(name, _)| text.len() >= 3 && name.starts_with(text))
+        .map(|(_, weekday)| *weekday)
+}
+
+/// Relative offsets like `+1h`, `-30m`, `2d` or `1w`, in days and minutes.
+fn parse_offset(text: &str) -> Option<(i64, i64)> {
+    let (sign, rest) = match text.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let unit = rest.chars().last()?;
+    let amount: i64 = rest[..rest.len() - unit.len_utf8()].parse().ok()?;
+    match unit {
+        'm' => Some((0, sign * amount)),
+        'h' => Some((0, sign * amount * 60)),
+        'd' => Some((sign * amount, 0)),
+        'w' => Some((sign * amount * 7, 0)),
+        _ => None,
+    }
+}
+
+fn parse_clock(text: &str) -> Option<(u32, u32)> {
+    let (hrs, min) = match text.split_once(':') {
+        Some((hrs, min)) => (hrs.parse().ok()?, min.parse().ok()?),
+        None => (text.parse().ok()?, 0),
+    };
+    if hrs > 23 || min > 59 {
+        None
+    } else {
+        Some((hrs, min))
+    }
+}
+
+fn parse_calendar_date(text: &str, today: NaiveDate) -> Option<NaiveDate> {
+    if let Ok(date) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+        return Some(date);
+    }
+    // 24.03.2025, 24.03. or 24.3
+    let mut parts = text.trim_end_matches('.').split('.');
+    let day = parts.next()?.parse().ok()?;
+    let month = parts.next()?.parse().ok()?;
+    let year = match parts.next() {
+        Some(year) => year.parse().ok()?,
+        None => today.year(),
+    };
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+fn parse_expression(text: &str, today: NaiveDate) -> Option<Expression> {
+    let mut expression = Expression::default();
+    let tokens = text.trim().to_lowercase();
+    let tokens: Vec<&str> = tokens.split_whitespace().collect();
+    if tokens.is_empty() {
+        expression.date = Some(today);
+    }
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i];
+        let next = tokens.get(i + 1).copied();
+        match token {
+            "now" | "n" => expression.date = Some(today),
+            "today" => expression.date = Some(today),
+            "yesterday" | "yd" => expression.date = Some(today.pred_opt()?),
+            "tomorrow" => expression.date = Some(today.succ_opt()?),
+            "noon" => expression.time = Some((12, 0)),
+            "midnight" => expression.time = Some((0, 0)),
+            "last" | "next" => {
+                let weekday = parse_weekday(next?)?;
+                let mut date = today;
+                loop {
+                    date = if token == "last" { date.pred_opt()? } else { date.succ_opt()? };
+                    if date.weekday() == weekday {
+                        break;
+                    }
+                }
+                expression.date = Some(date);
+                i += 1;
+            },
+            _ => {
+                if let Some((days, minutes)) = parse_offset(token) {
+                    let sign = if next == Some("ago") {
+                        i += 1;
+                        -1
+                    } else {
+                        1
+                    };
+                    expression.days += sign * days;
+                    expression.minutes += sign * minutes;
+                } else if let Some(time) = parse_clock(token) {
+                    expression.time = Some(time);
+                } else if let Some(date) = parse_calendar_date(token, today) {
+                    expression.date = Some(date);
+                } else if let Some(weekday) = parse_weekday(token) {
+                    // The most recent such day, possibly today.
+                    let mut date = today;
+                    while date.weekday() != weekday {
+                        date = date.pred_opt()?;
+                    }
+                    expression.date = Some(date);
+                } else {
+                    return None;
+                }
+            },
+        }
+        i += 1;
+    }
+    Some(expression)
+}
+
+/// The day (as used for data files) of `now`.
+pub fn today(now: NaiveDateTime) -> NaiveDate {
+    Slot::from_datetime(now).0
+}
+
+/// Parse a day like `today`, `yesterday`, `last friday`, `fri`, `2025-03-24`,
+/// `24.03.` or `-2d`. Any time of day in the text is ignored.
+pub fn parse_day(text: &str, now: NaiveDateTime) -> Option<NaiveDate> {
+    let expression = parse_expression(text, today(now))?;
+    let date = expression.date.unwrap_or_else(|| today(now));
+    Some(date + Duration::days(expression.days))
+}
+
+/// Parse a point in time like `now`, `14:07`, `14`, `noon`, `+1h`,
+/// `30m ago`, `yesterday 18:00` or `last friday noon` into a day and the
+/// slot it falls into. Times before `DAY_START` belong to the previous day,
+/// just like in the data files. The time is rounded to a slot boundary.
+///
+/// A bare time of day like `14:07` refers to `day`, while `now` and relative
+/// times refer to `now`.
+pub fn parse_time_on(
+    text: &str,
+    day: NaiveDate,
+    now: NaiveDateTime,
+    rounding: Rounding,
+) -> Option<(NaiveDate, Slot)> {
+    let today = today(now);
+    let expression = parse_expression(text, today)?;
+    let date = expression
+        .date
+        .unwrap_or(if expression.time.is_some() { day } else { today })
+        + Duration::days(expression.days);
+    let (hour, minute) = expression.time.unwrap_or((now.hour(), now.minute()));
+    let mut time = date.and_time(NaiveTime::from_hms_opt(hour, minute, 0)?);
+    if (hour as usize) < *DAY_START / SLOTS_PER_HOUR {
+        time += Duration::days(1);
+    }
+    time += Duration::minutes(expression.minutes);
+    Some(Slot::from_datetime(round(time, rounding)))
+}
+
+/// Round a time to a slot boundary.
+pub fn round(time: NaiveDateTime, rounding: Rounding) -> NaiveDateTime {
+    let minutes_per_slot = 60 / SLOTS_PER_HOUR;
+    let minute_of_day = (time.hour() * 60 + time.minute()) as usize;
+    let rounded = rounding.round(minute_of_day, minutes_per_slot);
+    time.date().and_time(NaiveTime::MIN) + Duration::minutes(rounded as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(text: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+    }
+
+    /// A Wednesday afternoon.
+    fn now() -> NaiveDateTime {
+        date("2025-03-26").and_hms_opt(14, 7, 0).unwrap()
+    }
+
+    fn time(text: &str, rounding: Rounding) -> Option<(NaiveDate, Slot)> {
+        parse_time_on(text, today(now()), now(), rounding)
+    }
+
+    #[test]
+    fn parses_times_of_day_and_rounds_them() {
+        assert_eq!(time("14:07", Rounding::Up), Some((date("2025-03-26"), Slot::from_time(14, 15))));
+        assert_eq!(time("14:07", Rounding::Down), Some((date("2025-03-26"), Slot::from_time(14, 0))));
+        assert_eq!(time("now", Rounding::Nearest), Some((date("2025-03-26"), Slot::from_time(14, 0))));
+        assert_eq!(time("n", Rounding::Up), time("now", Rounding::Up));
+        assert_eq!(time("", Rounding::Up), time("now", Rounding::Up));
+        assert_eq!(time("9", Rounding::Up), Some((date("2025-03-26"), Slot::from_time(9, 0))));
+        assert_eq!(time("noon", Rounding::Up), Some((date("2025-03-26"), Slot::from_time(12, 0))));
+    }
+
+    #[test]
+    fn times_before_the_day_start_belong_to_the_previous_day() {
+        assert_eq!(time("2", Rounding::Up), Some((date("2025-03-26"), Slot::from_time(2, 0))));
+        assert_eq!(time("midnight", Rounding::Up), Some((date("2025-03-26"), Slot::from_time(0, 0))));
+        assert_eq!(today(date("2025-03-27").and_hms_opt(3, 59, 0).unwrap()), date("2025-03-26"));
+        assert_eq!(today(date("2025-03-27").and_hms_opt(4, 0, 0).unwrap()), date("2025-03-27"));
+    }
+
+    #[test]
+    fn parses_relative_times_and_days() {
+        assert_eq!(time("30m ago", Rounding::Down), Some((date("2025-03-26"), Slot::from_time(13, 30))));
+        assert_eq!(time("+1h", Rounding::Up), Some((date("2025-03-26"), Slot::from_time(15, 15))));
+        assert_eq!(time("-1d", Rounding::Down), Some((date("2025-03-25"), Slot::from_time(14, 0))));
+        assert_eq!(time("yesterday 18", Rounding::Up), Some((date("2025-03-25"), Slot::from_time(18, 0))));
+        assert_eq!(time("yd 18:30", Rounding::Up), Some((date("2025-03-25"), Slot::from_time(18, 30))));
+        assert_eq!(time("last friday noon", Rounding::Up), Some((date("2025-03-21"), Slot::from_time(12, 0))));
+    }
+
+    #[test]
+    fn bare_times_refer_to_the_given_day() {
+        let day = date("2025-03-20");
+        assert_eq!(parse_time_on("9", day, now(), Rounding::Up), Some((day, Slot::from_time(9, 0))));
+        assert_eq!(parse_time_on("now", day, now(), Rounding::Up), time("now", Rounding::Up));
+        assert_eq!(
+            parse_time_on("yesterday 9", day, now(), Rounding::Up),
+            Some((date("2025-03-25"), Slot::from_time(9, 0)))
+        );
+    }
+
+    #[test]
+    fn parses_days() {
+        assert_eq!(parse_day("today", now()), Some(date("2025-03-26")));
+        assert_eq!(parse_day("yesterday", now()), Some(date("2025-03-25")));
+        assert_eq!(parse_day("tomorrow", now()), Some(date("2025-03-27")));
+        assert_eq!(parse_day("-2d", now()), Some(date("2025-03-24")));
+        assert_eq!(parse_day("1w ago", now()), Some(date("2025-03-19")));
+        // The most recent such day, possibly today.
+        assert_eq!(parse_day("fri", now()), Some(date("2025-03-21")));
+        assert_eq!(parse_day("wednesday", now()), Some(date("2025-03-26")));
+        assert_eq!(parse_day("last wednesday", now()), Some(date("2025-03-19")));
+        assert_eq!(parse_day("next monday", now()), Some(date("2025-03-31")));
+        assert_eq!(parse_day("2025-03-24", now()), Some(date("2025-03-24")));
+        assert_eq!(parse_day("24.03.", now()), Some(date("2025-03-24")));
+        assert_eq!(parse_day("24.3.2024", now()), Some(date("2024-03-24")));
+        assert_eq!(parse_day("yesterday 18:00", now()), Some(date("2025-03-25")));
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        assert_eq!(time("25:00", Rounding::Up), None);
+        assert_eq!(time("12:60", Rounding::Up), None);
+        assert_eq!(time("banana", Rounding::Up), None);
+        assert_eq!(time("last", Rounding::Up), None);
+        assert_eq!(parse_day("31.02.", now()), None);
+        assert_eq!(parse_day("fr", now()), None);
+    }
+}
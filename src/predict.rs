@@ -0,0 +1,79 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use crate::activity::Activity;
+use crate::day::{Day, Slot};
+use crate::settings::Settings;
+
+/// How many days back to learn from.
+const HISTORY_DAYS: i64 = 8 * 7;
+/// Weight of a day relative to the one after it.
+const DECAY: f32 = 0.97;
+
+/// How likely each activity of the config is for a span, learned from the
+/// days before.
+pub struct Prediction {
+    /// Indices into `settings.activities`, the most likely first. Activities
+    /// that never occurred keep their order from the config.
+    pub order: Vec<usize>,
+    /// The most likely activity, unless there is no history at all.
+    pub likely: Option<usize>,
+}
+
+/// Scale `scores` so they add up to 1.
+fn normalize(scores: &mut [f32]) {
+    let sum: f32 = scores.iter().sum();
+    if sum > 0. {
+        scores.iter_mut().for_each(|s| *s /= sum);
+    }
+}
+
+impl Prediction {
+    /// Predict the activity from `start` to `end` on `date`, looking at the
+    /// previous days for what was done at that time of day, what followed
+    /// the activity that comes before `start` on `day` and what was done
+    /// at all. Recent days count more, and days on the same weekday twice.
+    pub fn new(settings: &Settings, date: NaiveDate, day: &Day, start: Slot, end: Slot) -> Self {
+        let index = |activity: &Activity| settings.activities.iter().position(|a| a == activity);
+        let previous = day.entry_before(start).and_then(|(_, a)| index(a));
+        let count = settings.activities.len();
+        let mut at_time = vec![0.; count];
+        let mut after_previous = vec![0.; count];
+        let mut overall = vec![0.; count];
+        for days_ago in 1..=HISTORY_DAYS {
+            let past = date - Duration::days(days_ago);
+            let file = settings.get_filename_by_day(past);
+            if !file.exists() {
+                continue;
+            }
+            let mut weight = DECAY.powi(days_ago as i32);
+            if past.weekday() == date.weekday() {
+                weight *= 2.;
+            }
+            let past_day = Day::read(&file);
+            for (slot, activity) in past_day.time_slots.iter().enumerate() {
+                if let Some(i) = activity.as_ref().and_then(index) {
+                    overall[i] += weight;
+                    if *start <= slot && slot < *end {
+                        at_time[i] += weight;
+                    }
+                }
+            }
+            for pair in past_day.entries().windows(2) {
+                if previous.is_some() && index(pair[0].2) == previous {
+                    if let Some(i) = index(pair[1].2) {
+                        after_previous[i] += weight;
+                    }
+                }
+            }
+        }
+        normalize(&mut at_time);
+        normalize(&mut after_previous);
+        normalize(&mut overall);
+        let scores = (0..count)
+            .map(|i| 0.5 * at_time[i] + 0.3 * after_previous[i] + 0.2 * overall[i])
+            .collect::<Vec<_>>();
+        let mut order = (0..count).collect::<Vec<_>>();
+        order.sort_by(|a, b| scores[*b].total_cmp(&scores[*a]));
+        let likely = order.first().copied().filter(|i| scores[*i] > 0.);
+        Prediction { order, likely }
+    }
+}
@@ -0,0 +1,88 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::path::PathBuf;
+use colored::Colorize;
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::FileHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use crate::settings::Settings;
+
+pub const HISTORY_FILENAME: &str = "prompt_history.txt";
+
+/// Completes and hints the whole line from a list that depends on what is
+/// asked for, like activity names or earlier comments.
+struct Candidates(Vec<String>);
+
+impl Candidates {
+    fn matching<'a>(&'a self, line: &'a str) -> impl Iterator<Item = &'a String> + 'a {
+        let line = line.to_lowercase();
+        self.0.iter().filter(move |c| c.to_lowercase().starts_with(&line))
+    }
+}
+
+impl Completer for Candidates {
+    type Candidate = String;
+
+    fn complete(&self, line: &str, pos: usize, _: &Context<'_>) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok((0, self.matching(&line[..pos]).cloned().collect()))
+    }
+}
+
+impl Hinter for Candidates {
+    type Hint = String;
+
+    fn hint(&self, line: &str, pos: usize, _: &Context<'_>) -> Option<String> {
+        if line.is_empty() || pos < line.len() {
+            return None;
+        }
+        let candidate = self.matching(line).next()?;
+        Some(candidate.chars().skip(line.chars().count()).collect())
+    }
+}
+
+impl Highlighter for Candidates {
+    fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
+        Cow::Owned(hint.dimmed().to_string())
+    }
+}
+
+impl Validator for Candidates {}
+
+impl Helper for Candidates {}
+
+thread_local! {
+    static EDITOR: RefCell<Option<(Editor<Candidates, FileHistory>, PathBuf)>> = const { RefCell::new(None) };
+}
+
+/// Set up line editing for the prompts, with a history shared by all runs.
+pub fn init(settings: &Settings) {
+    let mut editor = Editor::new().expect("line editor");
+    let history = settings.data_dir.join(HISTORY_FILENAME);
+    // There is no history the first time.
+    let _ = editor.load_history(&history);
+    EDITOR.with(|e| *e.borrow_mut() = Some((editor, history)));
+}
+
+/// Read a line, completing from `candidates`. `None` if the input ended or
+/// was interrupted.
+pub fn read_line(prompt: &str, candidates: Vec<String>) -> Option<String> {
+    EDITOR.with(|e| {
+        let mut e = e.borrow_mut();
+        let (editor, history) = e.as_mut().expect("prompt::init");
+        editor.set_helper(Some(Candidates(candidates)));
+        let line = match editor.readline(prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Eof | ReadlineError::Interrupted) => return None,
+            Err(error) => panic!("readline: {}", error),
+        };
+        if !line.trim().is_empty() {
+            editor.add_history_entry(line.as_str()).expect("history");
+            editor.save_history(history).expect("write history");
+        }
+        Some(line)
+    })
+}
@@ -0,0 +1,148 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+
+/// First and last day of a month.
+fn month(year: i32, month: u32) -> Option<(NaiveDate, NaiveDate)> {
+    let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+    let next = if month == 12 {
+        NaiveDate::from_ymd_opt(year + 1, 1, 1)?
+    } else {
+        NaiveDate::from_ymd_opt(year, month + 1, 1)?
+    };
+    Some((first, next.pred_opt()?))
+}
+
+/// First day of the month `back` months before the one of `date`.
+pub fn month_start(date: NaiveDate, back: usize) -> NaiveDate {
+    let months = date.year() * 12 + date.month0() as i32 - back as i32;
+    NaiveDate::from_ymd_opt(months.div_euclid(12), months.rem_euclid(12) as u32 + 1, 1).unwrap()
+}
+
+fn quarter(year: i32, quarter: u32) -> Option<(NaiveDate, NaiveDate)> {
+    if !(1..=4).contains(&quarter) {
+        return None;
+    }
+    let (first, _) = month(year, quarter * 3 - 2)?;
+    let (_, last) = month(year, quarter * 3)?;
+    Some((first, last))
+}
+
+fn year(year: i32) -> Option<(NaiveDate, NaiveDate)> {
+    Some((NaiveDate::from_ymd_opt(year, 1, 1)?, NaiveDate::from_ymd_opt(year, 12, 31)?))
+}
+
+fn iso_week(year: i32, week: u32) -> Option<(NaiveDate, NaiveDate)> {
+    let first = NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)?;
+    Some((first, first + Duration::days(6)))
+}
+
+/// A calendar-aligned period relative to `today`, e.g. `this week` (ISO
+/// week), `last month`, `Q2` (of the current year), `2025-Q2`, `2025-03`,
+/// `2025-W12` or `2025`.
+pub fn parse_period(text: &str, today: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+    let text = text.trim().to_lowercase().split_whitespace().collect::<Vec<_>>().join("-");
+    let this_week = iso_week(today.iso_week().year(), today.iso_week().week());
+    let last_week = today - Duration::days(7);
+    let last_month = today.with_day(1)?.pred_opt()?;
+    match text.as_str() {
+        "today" => return Some((today, today)),
+        "yesterday" => return Some((today.pred_opt()?, today.pred_opt()?)),
+        "week" | "this-week" => return this_week,
+        "last-week" => return iso_week(last_week.iso_week().year(), last_week.iso_week().week()),
+        "month" | "this-month" => return month(today.year(), today.month()),
+        "last-month" => return month(last_month.year(), last_month.month()),
+        "quarter" | "this-quarter" => return quarter(today.year(), today.month0() / 3 + 1),
+        "year" | "this-year" => return year(today.year()),
+        "last-year" => return year(today.year() - 1),
+        _ => {},
+    }
+    if let Some(q) = text.strip_prefix('q') {
+        return quarter(today.year(), q.parse().ok()?);
+    }
+    let (y, rest) = match text.split_once('-') {
+        Some((y, rest)) => (y.parse::<i32>().ok()?, rest),
+        None => return year(text.parse().ok()?),
+    };
+    if let Some(q) = rest.strip_prefix('q') {
+        quarter(y, q.parse().ok()?)
+    } else if let Some(w) = rest.strip_prefix('w') {
+        iso_week(y, w.parse().ok()?)
+    } else {
+        month(y, rest.parse().ok()?)
+    }
+}
+
+/// All days from `from` to `to`, both included.
+pub fn days(from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+    from.iter_days().take_while(|d| *d <= to).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(text: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+    }
+
+    fn period(text: &str, today: &str) -> Option<(NaiveDate, NaiveDate)> {
+        parse_period(text, date(today))
+    }
+
+    #[test]
+    fn parses_relative_periods() {
+        let today = "2025-03-26";
+        assert_eq!(period("today", today), Some((date("2025-03-26"), date("2025-03-26"))));
+        assert_eq!(period("yesterday", today), Some((date("2025-03-25"), date("2025-03-25"))));
+        assert_eq!(period("this week", today), Some((date("2025-03-24"), date("2025-03-30"))));
+        assert_eq!(period("Last Week", today), Some((date("2025-03-17"), date("2025-03-23"))));
+        assert_eq!(period("month", today), Some((date("2025-03-01"), date("2025-03-31"))));
+        assert_eq!(period("last month", today), Some((date("2025-02-01"), date("2025-02-28"))));
+        assert_eq!(period("this quarter", today), Some((date("2025-01-01"), date("2025-03-31"))));
+        assert_eq!(period("last year", today), Some((date("2024-01-01"), date("2024-12-31"))));
+    }
+
+    #[test]
+    fn relative_periods_cross_year_boundaries() {
+        assert_eq!(period("last month", "2025-01-15"), Some((date("2024-12-01"), date("2024-12-31"))));
+        // 2025-01-01 is in the first ISO week of 2025, which starts in 2024.
+        assert_eq!(period("this week", "2025-01-01"), Some((date("2024-12-30"), date("2025-01-05"))));
+        assert_eq!(period("last week", "2025-01-01"), Some((date("2024-12-23"), date("2024-12-29"))));
+    }
+
+    #[test]
+    fn finds_the_start_of_earlier_months() {
+        assert_eq!(month_start(date("2025-03-26"), 0), date("2025-03-01"));
+        assert_eq!(month_start(date("2025-03-26"), 1), date("2025-02-01"));
+        assert_eq!(month_start(date("2025-03-26"), 3), date("2024-12-01"));
+        assert_eq!(month_start(date("2025-01-31"), 25), date("2022-12-01"));
+    }
+
+    #[test]
+    fn parses_absolute_periods() {
+        let today = "2025-03-26";
+        assert_eq!(period("Q2", today), Some((date("2025-04-01"), date("2025-06-30"))));
+        assert_eq!(period("2024-Q4", today), Some((date("2024-10-01"), date("2024-12-31"))));
+        assert_eq!(period("2024-02", today), Some((date("2024-02-01"), date("2024-02-29"))));
+        assert_eq!(period("2025-W12", today), Some((date("2025-03-17"), date("2025-03-23"))));
+        assert_eq!(period("2024", today), Some((date("2024-01-01"), date("2024-12-31"))));
+    }
+
+    #[test]
+    fn rejects_invalid_periods() {
+        let today = "2025-03-26";
+        assert_eq!(period("Q5", today), None);
+        assert_eq!(period("2025-13", today), None);
+        assert_eq!(period("2025-W54", today), None);
+        assert_eq!(period("next week", today), None);
+    }
+
+    #[test]
+    fn lists_days_including_both_ends() {
+        assert_eq!(
+            days(date("2025-03-30"), date("2025-04-01")),
+            vec![date("2025-03-30"), date("2025-03-31"), date("2025-04-01")]
+        );
+        assert_eq!(days(date("2025-03-26"), date("2025-03-26")), vec![date("2025-03-26")]);
+        assert!(days(date("2025-03-26"), date("2025-03-25")).is_empty());
+    }
+}
@@ -0,0 +1,104 @@
+//! Non-interactive versions of the commands that record activities. They never
+//! prompt and report problems as errors instead, so that they can be used from
+//! scripts.
+
+use chrono::NaiveDate;
+use crate::activity::Activity;
+use crate::day::Slot;
+use crate::{get_time, UI};
+
+fn find_activity(ui: &UI, name: &str) -> Result<Activity, String> {
+    Activity::find(ui.settings, name).ok_or_else(|| format!("Unknown activity: {}", name))
+}
+
+fn parse_time(ui: &UI, text: &str) -> Result<(NaiveDate, Slot), String> {
+    get_time(text, ui.date, ui.settings).ok_or_else(|| format!("Invalid time: {}", text))
+}
+
+/// Parse a range like `09:00-11:30` or `9-now` on the current day.
+pub fn parse_range(ui: &UI, text: &str) -> Result<(Slot, Slot), String> {
+    let (start, end) = text
+        .split_once('-')
+        .ok_or_else(|| format!("Invalid range, expected START-END: {}", text))?;
+    let (start_date, start) = parse_time(ui, start)?;
+    let (end_date, end) = parse_time(ui, end)?;
+    if start_date != ui.date || end_date != ui.date {
+        return Err(format!("The range {} is not on {}.", text, ui.date));
+    }
+    if *end <= *start {
+        return Err("End time <= start time!".to_string());
+    }
+    Ok((start, end))
+}
+
+/// `add ACTIVITY START-END`
+pub fn add(ui: &mut UI, name: &str, range: &str, comment: Option<String>, tags: Vec<String>) -> Result<(), String> {
+    let mut activity = find_activity(ui, name)?;
+    let (start, end) = parse_range(ui, range)?;
+    activity.tags = tags;
+    ui.fill(start, end, &activity);
+    if comment.is_some() {
+        ui.day.time_slots[*start].as_mut().unwrap().comment = comment;
+    }
+    println!("{}-{} - {}", start, end, ui.day.time_slots[*start].as_ref().unwrap());
+    ui.save();
+    Ok(())
+}
+
+/// `comment [--at TIME] TEXT`: Comment the entry at the given time, or the
+/// last entry.
+pub fn comment(ui: &mut UI, at: Option<String>, text: String) -> Result<(), String> {
+    let slot = if let Some(at) = at {
+        let (date, slot) = parse_time(ui, &at)?;
+        if date != ui.date {
+            return Err(format!("{} is not on {}.", at, ui.date));
+        }
+        slot
+    } else {
+        ui.day
+            .time_slots
+            .iter()
+            .rposition(|o| o.is_some())
+            .map(Slot)
+            .ok_or_else(|| "Please add a recent activity first!".to_string())?
+    };
+    let entry = ui.day.time_slots[*slot]
+        .as_mut()
+        .ok_or_else(|| format!("There is no activity at {}.", slot))?;
+    entry.comment = Some(text);
+    println!("{} - {}", slot, entry);
+    ui.save();
+    Ok(())
+}
+
+/// `split TIME... ACTIVITY...`: Split the time since the last entry at the
+/// given times and assign one activity to each part.
+pub fn split(ui: &mut UI, args: &[String]) -> Result<(), String> {
+    ui.require_today("split")?;
+    // The number of times follows from the number of arguments, so that
+    // shortcuts like 'n' are not mistaken for times.
+    let (times, names) = args.split_at(args.len() / 2);
+    if times.is_empty() || names.len() != times.len() + 1 {
+        return Err("Usage: split TIME... ACTIVITY... (one activity more than times)".to_string());
+    }
+    let mut bounds = vec![ui.day.now_or_last_entry()];
+    for time in times {
+        let (date, slot) = parse_time(ui, time)?;
+        if date != ui.date || *slot <= **bounds.last().unwrap() || *slot > *Slot::now() {
+            return Err(format!(
+                "{} is not between {} and now.",
+                time,
+                bounds.last().unwrap()
+            ));
+        }
+        bounds.push(slot);
+    }
+    bounds.push(Slot::now().next());
+    let activities = names.iter().map(|name| find_activity(ui, name)).collect::<Result<Vec<_>, _>>()?;
+    for (bounds, activity) in bounds.windows(2).zip(activities.iter()) {
+        ui.fill(bounds[0], bounds[1], activity);
+        println!("{}-{} - {}", bounds[0], bounds[1], activity);
+    }
+    ui.save();
+    Ok(())
+}
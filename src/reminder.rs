@@ -0,0 +1,247 @@
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+use chrono::{Datelike, Local, NaiveDateTime, NaiveTime, Weekday};
+use colored::Colorize;
+use serde_derive::{Deserialize, Serialize};
+use crate::balance::default_workdays;
+use crate::day::{Day, Slot};
+use crate::settings::Settings;
+use crate::{timer, SLOTS_PER_HOUR};
+
+/// When to remind about time that was not recorded.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReminderSettings {
+    /// Remind once this many slots since the last entry are empty, and again
+    /// after each further as many.
+    #[serde(default = "default_every_slots")]
+    pub every_slots: usize,
+    /// No reminders from `quiet_from` until `quiet_until`, which may be on
+    /// the next day.
+    #[serde(default = "default_quiet_from")]
+    pub quiet_from: NaiveTime,
+    #[serde(default = "default_quiet_until")]
+    pub quiet_until: NaiveTime,
+    /// Days on which to remind at all.
+    #[serde(default = "default_workdays")]
+    pub weekdays: Vec<Weekday>,
+    /// Called as `<notifier> <summary> <body>`.
+    #[serde(default = "default_notifier")]
+    pub notifier: String,
+}
+
+fn default_every_slots() -> usize {
+    SLOTS_PER_HOUR
+}
+
+fn default_quiet_from() -> NaiveTime {
+    NaiveTime::from_hms_opt(20, 0, 0).unwrap()
+}
+
+fn default_quiet_until() -> NaiveTime {
+    NaiveTime::from_hms_opt(8, 0, 0).unwrap()
+}
+
+fn default_notifier() -> String {
+    "notify-send".to_string()
+}
+
+impl Default for ReminderSettings {
+    fn default() -> Self {
+        ReminderSettings {
+            every_slots: default_every_slots(),
+            quiet_from: default_quiet_from(),
+            quiet_until: default_quiet_until(),
+            weekdays: default_workdays(),
+            notifier: default_notifier(),
+        }
+    }
+}
+
+pub trait Notifier {
+    fn notify(&mut self, summary: &str, body: &str);
+}
+
+/// Desktop notifications through a command like `notify-send`.
+pub struct CommandNotifier {
+    pub command: String,
+}
+
+impl Notifier for CommandNotifier {
+    fn notify(&mut self, summary: &str, body: &str) {
+        let status = Command::new(&self.command).arg(summary).arg(body).status();
+        if !status.is_ok_and(|s| s.success()) {
+            eprintln!("{}{}", "Could not send a notification with ".red(), self.command);
+        }
+    }
+}
+
+/// Prints the notifications instead.
+pub struct PrintNotifier;
+
+impl Notifier for PrintNotifier {
+    fn notify(&mut self, summary: &str, body: &str) {
+        println!("{} {}: {}", Local::now().format("%H:%M"), summary.bright_blue(), body);
+    }
+}
+
+impl ReminderSettings {
+    fn is_quiet(&self, time: NaiveTime) -> bool {
+        if self.quiet_from <= self.quiet_until {
+            self.quiet_from <= time && time < self.quiet_until
+        } else {
+            self.quiet_from <= time || time < self.quiet_until
+        }
+    }
+
+    /// Notify if the empty slots between the last entry of `day` and `slot`
+    /// (the one `now` falls into) just reached a multiple of `every_slots`.
+    pub fn check(&self, day: &Day, slot: Slot, now: NaiveDateTime, notifier: &mut dyn Notifier) -> bool {
+        if self.is_quiet(now.time()) || !self.weekdays.contains(&now.weekday()) {
+            return false;
+        }
+        let since = day.entry_before(slot).map_or(Slot(0), |(s, _)| s.next());
+        let empty = *slot - *since;
+        if empty == 0 || !empty.is_multiple_of(self.every_slots.max(1)) {
+            return false;
+        }
+        let minutes = empty * 60 / SLOTS_PER_HOUR;
+        notifier.notify(
+            "Time to track your time!",
+            &format!("Nothing recorded since {} ({}:{:02} hours).", since, minutes / 60, minutes % 60),
+        );
+        true
+    }
+}
+
+/// Check at the start of every slot until killed. There is nothing to remind
+/// about while a timer is running.
+pub fn run(settings: &Settings, reminder: &ReminderSettings, notifier: &mut dyn Notifier) {
+    let mut checked = None;
+    loop {
+        let now = Local::now().naive_local();
+        let (date, slot) = Slot::from_datetime(now);
+        if checked != Some((date, *slot)) {
+            checked = Some((date, *slot));
+            if timer::read(settings).is_none() {
+                let day = Day::load(settings, date);
+                reminder.check(&day, slot, now, notifier);
+            }
+        }
+        thread::sleep(Duration::from_secs(30));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use super::*;
+    use crate::settings::Settings;
+
+    /// Records the notifications instead of sending them.
+    #[derive(Default)]
+    struct Recorder {
+        notifications: Vec<(String, String)>,
+    }
+
+    impl Notifier for Recorder {
+        fn notify(&mut self, summary: &str, body: &str) {
+            self.notifications.push((summary.to_string(), body.to_string()));
+        }
+    }
+
+    /// Work from 08:00 to 09:00 on a Monday.
+    fn day() -> Day {
+        let settings = Settings::with_activities(&["Work"]);
+        let mut day = Day::default();
+        for slot in *Slot::from_time(8, 0)..*Slot::from_time(9, 0) {
+            day.time_slots[slot] = Some(settings.activities[0].clone());
+        }
+        day
+    }
+
+    fn at(date: &str, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap().and_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    /// Check on the Monday of `day` at the given time.
+    fn check(reminder: &ReminderSettings, day: &Day, hour: u32, minute: u32, notifier: &mut Recorder) -> bool {
+        let now = at("2025-03-24", hour, minute);
+        reminder.check(day, Slot::from_datetime(now).1, now, notifier)
+    }
+
+    #[test]
+    fn reminds_once_the_gap_reaches_the_threshold() {
+        let reminder = ReminderSettings::default();
+        let mut notifier = Recorder::default();
+        assert!(!check(&reminder, &day(), 9, 0, &mut notifier));
+        assert!(!check(&reminder, &day(), 9, 45, &mut notifier));
+        assert!(notifier.notifications.is_empty());
+        assert!(check(&reminder, &day(), 10, 0, &mut notifier));
+        assert_eq!(
+            notifier.notifications,
+            [("Time to track your time!".to_string(), "Nothing recorded since 09:00 (1:00 hours).".to_string())]
+        );
+    }
+
+    #[test]
+    fn reminds_again_only_after_another_threshold() {
+        let reminder = ReminderSettings::default();
+        let mut notifier = Recorder::default();
+        assert!(check(&reminder, &day(), 10, 0, &mut notifier));
+        assert!(!check(&reminder, &day(), 10, 15, &mut notifier));
+        assert!(!check(&reminder, &day(), 10, 45, &mut notifier));
+        assert!(check(&reminder, &day(), 11, 0, &mut notifier));
+        assert_eq!(notifier.notifications.len(), 2);
+        assert_eq!(notifier.notifications[1].1, "Nothing recorded since 09:00 (2:00 hours).");
+    }
+
+    #[test]
+    fn uses_the_configured_threshold() {
+        let reminder = ReminderSettings { every_slots: 2, ..ReminderSettings::default() };
+        let mut notifier = Recorder::default();
+        assert!(!check(&reminder, &day(), 9, 15, &mut notifier));
+        assert!(check(&reminder, &day(), 9, 30, &mut notifier));
+        assert!(!check(&reminder, &day(), 9, 45, &mut notifier));
+        assert!(check(&reminder, &day(), 10, 0, &mut notifier));
+    }
+
+    #[test]
+    fn counts_from_the_start_of_an_empty_day() {
+        let reminder = ReminderSettings::default();
+        let mut notifier = Recorder::default();
+        assert!(check(&reminder, &Day::default(), 9, 0, &mut notifier));
+        assert_eq!(notifier.notifications[0].1, "Nothing recorded since 04:00 (5:00 hours).");
+    }
+
+    #[test]
+    fn stays_quiet_outside_the_configured_hours() {
+        let reminder = ReminderSettings::default();
+        let mut notifier = Recorder::default();
+        // From 20:00 until 08:00 the next morning.
+        assert!(!check(&reminder, &day(), 20, 0, &mut notifier));
+        assert!(!check(&reminder, &day(), 23, 0, &mut notifier));
+        assert!(!check(&reminder, &Day::default(), 7, 0, &mut notifier));
+        assert!(check(&reminder, &Day::default(), 8, 0, &mut notifier));
+        assert!(check(&reminder, &day(), 19, 0, &mut notifier));
+        assert_eq!(notifier.notifications.len(), 2);
+
+        let reminder = ReminderSettings {
+            quiet_from: NaiveTime::from_hms_opt(12, 0, 0).unwrap(),
+            quiet_until: NaiveTime::from_hms_opt(13, 0, 0).unwrap(),
+            ..ReminderSettings::default()
+        };
+        assert!(!check(&reminder, &day(), 12, 0, &mut notifier));
+        assert!(check(&reminder, &day(), 13, 0, &mut notifier));
+        assert!(check(&reminder, &day(), 22, 0, &mut notifier));
+    }
+
+    #[test]
+    fn stays_quiet_on_other_weekdays() {
+        let reminder = ReminderSettings::default();
+        let mut notifier = Recorder::default();
+        let saturday = at("2025-03-29", 10, 0);
+        assert!(!reminder.check(&day(), Slot::from_datetime(saturday).1, saturday, &mut notifier));
+        assert!(notifier.notifications.is_empty());
+    }
+}
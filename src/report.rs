@@ -0,0 +1,260 @@
+use std::fmt::Write;
+use chrono::NaiveDate;
+use itertools::Itertools;
+use crate::activity::Activity;
+use crate::day::{Day, Slot};
+use crate::settings::Settings;
+use crate::{DAY_SLOTS, DAY_START, PRODUCTIVE_TARGET, SLOTS_PER_HOUR};
+
+/// Statistics over a range of days, rendered into standalone documents.
+pub struct Report<'s> {
+    settings: &'s Settings,
+    days: Vec<(NaiveDate, Option<Day>)>,
+}
+
+impl<'s> Report<'s> {
+    /// Load the data files for all given dates. Dates without a data file are
+    /// kept, but do not count towards the target.
+    pub fn load(settings: &'s Settings, dates: impl IntoIterator<Item = NaiveDate>) -> Self {
+        let days = dates
+            .into_iter()
+            .map(|date| {
+                let file = settings.get_filename_by_day(date);
+                (date, Some(file).filter(|f| f.exists()).map(|f| Day::read(&f)))
+            })
+            .collect();
+        Report { settings, days }
+    }
+
+    pub fn days(&self) -> &[(NaiveDate, Option<Day>)] {
+        &self.days
+    }
+
+    pub fn recorded_days(&self) -> impl Iterator<Item = (NaiveDate, &Day)> {
+        self.days
+            .iter()
+            .filter_map(|(date, day)| day.as_ref().map(|d| (*date, d)))
+    }
+
+    pub fn hours_productive(&self) -> f32 {
+        self.recorded_days().map(|(_, d)| d.hours_productive()).sum()
+    }
+
+    pub fn target(&self) -> f32 {
+        PRODUCTIVE_TARGET * self.recorded_days().count() as f32
+    }
+
+    /// Hours per configured activity, most hours first. Activities that were
+    /// never recorded are left out.
+    pub fn hours_by_activity(&self) -> Vec<(&Activity, f32)> {
+        self.settings
+            .activities
+            .iter()
+            .map(|activity| {
+                (
+                    activity,
+                    self.recorded_days().map(|(_, d)| d.hours_of(activity)).sum::<f32>(),
+                )
+            })
+            .filter(|(_, hours)| *hours > 0.)
+            .sorted_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap())
+            .collect()
+    }
+
+    /// All commented entries in chronological order.
+    pub fn comments(&self) -> Vec<(NaiveDate, Slot, &Activity)> {
+        self.recorded_days()
+            .flat_map(|(date, day)| {
+                day.entries()
+                    .into_iter()
+                    .filter(|(_, _, act)| act.comment.is_some())
+                    .map(move |(s, _, act)| (date, s, act))
+            })
+            .collect()
+    }
+
+    pub fn markdown(&self) -> String {
+        let mut md = String::new();
+        if let (Some((first, _)), Some((last, _))) = (self.days.first(), self.days.last()) {
+            writeln!(&mut md, "# Report {} - {}", first, last).expect("write");
+            writeln!(&mut md).expect("write");
+        }
+
+        writeln!(&mut md, "## Hours per Activity").expect("write");
+        writeln!(&mut md).expect("write");
+        writeln!(&mut md, "| Activity | Hours |").expect("write");
+        writeln!(&mut md, "|---|---:|").expect("write");
+        for (activity, hours) in self.hours_by_activity() {
+            writeln!(&mut md, "| {} | {:.2} |", activity.name, hours).expect("write");
+        }
+        writeln!(&mut md).expect("write");
+
+        writeln!(&mut md, "## Productivity").expect("write");
+        writeln!(&mut md).expect("write");
+        writeln!(&mut md, "| Day | Productive | Target | Difference |").expect("write");
+        writeln!(&mut md, "|---|---:|---:|---:|").expect("write");
+        for (date, day) in self.recorded_days() {
+            writeln!(
+                &mut md,
+                "| {} | {:.2} | {} | {:+.2} |",
+                date.format("%a, %d.%m."),
+                day.hours_productive(),
+                PRODUCTIVE_TARGET,
+                day.hours_productive() - PRODUCTIVE_TARGET
+            )
+            .expect("write");
+        }
+        writeln!(
+            &mut md,
+            "| **Total** | **{:.2}** | **{}** | **{:+.2}** |",
+            self.hours_productive(),
+            self.target(),
+            self.hours_productive() - self.target()
+        )
+        .expect("write");
+        writeln!(&mut md).expect("write");
+
+        writeln!(&mut md, "## Comments").expect("write");
+        for (date, comments) in &self.comments().into_iter().group_by(|(date, _, _)| *date) {
+            writeln!(&mut md).expect("write");
+            writeln!(&mut md, "### {}", date.format("%a, %d.%m.%Y")).expect("write");
+            writeln!(&mut md).expect("write");
+            for (_, slot, activity) in comments {
+                writeln!(
+                    &mut md,
+                    "- {} {}: {}",
+                    slot,
+                    activity.name,
+                    activity.comment.as_deref().unwrap_or("")
+                )
+                .expect("write");
+            }
+        }
+        md
+    }
+
+    pub fn html(&self) -> String {
+        let (first, last) = (self.days.first(), self.days.last());
+        let title = match (first, last) {
+            (Some((first, _)), Some((last, _))) => format!("Time-Tracker Report {} - {}", first, last),
+            _ => "Time-Tracker Report".to_string(),
+        };
+
+        let mut html = String::new();
+        writeln!(&mut html, "<!DOCTYPE html>").expect("write");
+        writeln!(&mut html, "<html><head><meta charset=\"utf-8\"><title>{}</title>", escape(&title)).expect("write");
+        writeln!(&mut html, "<style>").expect("write");
+        writeln!(&mut html, "body {{ font-family: sans-serif; background: #1e1e1e; color: #e5e5e5; }}").expect("write");
+        writeln!(&mut html, "table {{ border-collapse: collapse; }}").expect("write");
+        writeln!(&mut html, "td, th {{ padding: 2px 6px; text-align: left; }}").expect("write");
+        writeln!(&mut html, "table.grid td.slot {{ width: 6px; height: 18px; padding: 0; }}").expect("write");
+        writeln!(&mut html, "table.grid td.hour {{ border-left: 1px solid #555; }}").expect("write");
+        writeln!(&mut html, ".swatch {{ display: inline-block; width: 12px; height: 12px; }}").expect("write");
+        writeln!(&mut html, "</style></head><body>").expect("write");
+        writeln!(&mut html, "<h1>{}</h1>", escape(&title)).expect("write");
+
+        writeln!(&mut html, "<h2>Days</h2>").expect("write");
+        writeln!(&mut html, "<table class=\"grid\"><tr><th></th><th></th>").expect("write");
+        for h in 0..24 {
+            writeln!(
+                &mut html,
+                "<th colspan=\"{}\">{}</th>",
+                SLOTS_PER_HOUR,
+                (h + *DAY_START / SLOTS_PER_HOUR) % 24
+            )
+            .expect("write");
+        }
+        writeln!(&mut html, "</tr>").expect("write");
+        for (date, day) in &self.days {
+            write!(&mut html, "<tr><td>{}</td>", date.format("%a, %d.%m.")).expect("write");
+            if let Some(day) = day {
+                write!(&mut html, "<td>{:.1} hrs.</td>", day.hours_productive()).expect("write");
+                for (s, o) in day.time_slots.iter().enumerate() {
+                    let class = if s % SLOTS_PER_HOUR == 0 { "slot hour" } else { "slot" };
+                    if let Some(act) = o {
+                        let mut tooltip = format!("{} {}", Slot(s), act.name);
+                        if let Some(comment) = &act.comment {
+                            write!(&mut tooltip, " - {}", comment).expect("write");
+                        }
+                        write!(
+                            &mut html,
+                            "<td class=\"{}\" style=\"background: {}\" title=\"{}\"></td>",
+                            class,
+                            act.css_color(),
+                            escape(&tooltip)
+                        )
+                        .expect("write");
+                    } else {
+                        write!(&mut html, "<td class=\"{}\"></td>", class).expect("write");
+                    }
+                }
+            } else {
+                write!(&mut html, "<td colspan=\"{}\">no data</td>", DAY_SLOTS + 1).expect("write");
+            }
+            writeln!(&mut html, "</tr>").expect("write");
+        }
+        writeln!(&mut html, "</table>").expect("write");
+
+        writeln!(&mut html, "<h2>Hours per Activity</h2>").expect("write");
+        writeln!(&mut html, "<table>").expect("write");
+        for (activity, hours) in self.hours_by_activity() {
+            writeln!(
+                &mut html,
+                "<tr><td><span class=\"swatch\" style=\"background: {}\"></span> {}</td><td>{:.2} hrs.</td></tr>",
+                activity.css_color(),
+                escape(&activity.name),
+                hours
+            )
+            .expect("write");
+        }
+        writeln!(&mut html, "</table>").expect("write");
+
+        writeln!(&mut html, "<h2>Productivity</h2>").expect("write");
+        writeln!(&mut html, "<table>").expect("write");
+        for (date, day) in self.recorded_days() {
+            writeln!(
+                &mut html,
+                "<tr><td>{}</td><td>{:.2} / {} hrs.</td><td>{:.0}%</td></tr>",
+                date.format("%a, %d.%m."),
+                day.hours_productive(),
+                PRODUCTIVE_TARGET,
+                day.score() * 100.
+            )
+            .expect("write");
+        }
+        writeln!(
+            &mut html,
+            "<tr><th>Total</th><th>{:.2} / {} hrs.</th><th>{:+.2} hrs.</th></tr>",
+            self.hours_productive(),
+            self.target(),
+            self.hours_productive() - self.target()
+        )
+        .expect("write");
+        writeln!(&mut html, "</table>").expect("write");
+
+        writeln!(&mut html, "<h2>Comments</h2>").expect("write");
+        writeln!(&mut html, "<ul>").expect("write");
+        for (date, slot, activity) in self.comments() {
+            writeln!(
+                &mut html,
+                "<li>{} {} <span style=\"color: {}\">{}</span>: {}</li>",
+                date,
+                slot,
+                activity.css_color(),
+                escape(&activity.name),
+                escape(activity.comment.as_deref().unwrap_or(""))
+            )
+            .expect("write");
+        }
+        writeln!(&mut html, "</ul>").expect("write");
+        writeln!(&mut html, "</body></html>").expect("write");
+        html
+    }
+}
+
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,237 @@
+use std::cmp::Reverse;
+use chrono::NaiveDate;
+use colored::Colorize;
+use crate::activity::Activity;
+use crate::day::{Day, Slot};
+use crate::SLOTS_PER_HOUR;
+
+/// How many of the largest deviations are listed.
+const DEVIATIONS: usize = 5;
+
+/// Planned and recorded slots of one activity.
+pub struct ActivityReview<'d> {
+    pub activity: &'d Activity,
+    pub planned: usize,
+    pub actual: usize,
+    /// Planned slots in which the activity was recorded.
+    pub kept: usize,
+}
+
+impl ActivityReview<'_> {
+    pub fn adherence(&self) -> Option<f32> {
+        (self.planned > 0).then(|| self.kept as f32 / self.planned as f32 * 100.)
+    }
+}
+
+/// A range of planned slots in which something else (or nothing) was recorded.
+pub struct Deviation<'d> {
+    pub date: NaiveDate,
+    pub start: Slot,
+    pub end: Slot,
+    pub planned: &'d Activity,
+    pub actual: Option<&'d Activity>,
+}
+
+/// The plan of the days compared with what was recorded. Days without a plan
+/// are left out.
+pub struct Review<'d> {
+    pub activities: Vec<ActivityReview<'d>>,
+    pub deviations: Vec<Deviation<'d>>,
+    pub planned: usize,
+    pub kept: usize,
+}
+
+fn hours(slots: usize) -> f32 {
+    slots as f32 / SLOTS_PER_HOUR as f32
+}
+
+/// The entry of `activity`, added if there is none yet.
+fn entry<'r, 'd>(activities: &'r mut Vec<ActivityReview<'d>>, activity: &'d Activity) -> &'r mut ActivityReview<'d> {
+    match activities.iter().position(|a| a.activity == activity) {
+        Some(i) => &mut activities[i],
+        None => {
+            activities.push(ActivityReview { activity, planned: 0, actual: 0, kept: 0 });
+            activities.last_mut().unwrap()
+        },
+    }
+}
+
+impl<'d> Review<'d> {
+    pub fn new(days: &'d [(NaiveDate, Day)]) -> Self {
+        let mut activities = Vec::new();
+        let mut deviations: Vec<Deviation> = Vec::new();
+        for (date, day) in days.iter().filter(|(_, day)| day.is_planned()) {
+            for (slot, (planned, actual)) in day.planned.iter().zip(day.time_slots.iter()).enumerate() {
+                if let Some(actual) = actual {
+                    entry(&mut activities, actual).actual += 1;
+                }
+                let Some(planned) = planned else {
+                    continue;
+                };
+                let review = entry(&mut activities, planned);
+                review.planned += 1;
+                if actual.as_ref() == Some(planned) {
+                    review.kept += 1;
+                    continue;
+                }
+                match deviations.last_mut() {
+                    Some(last)
+                        if last.date == *date
+                            && *last.end == slot
+                            && last.planned == planned
+                            && last.actual == actual.as_ref() =>
+                    {
+                        last.end = Slot(slot + 1);
+                    },
+                    _ => deviations.push(Deviation {
+                        date: *date,
+                        start: Slot(slot),
+                        end: Slot(slot + 1),
+                        planned,
+                        actual: actual.as_ref(),
+                    }),
+                }
+            }
+        }
+        let planned = activities.iter().map(|a| a.planned).sum();
+        let kept = activities.iter().map(|a| a.kept).sum();
+        activities.sort_by_key(|a| Reverse(a.planned.max(a.actual)));
+        deviations.sort_by_key(|d| Reverse(*d.end - *d.start));
+        deviations.truncate(DEVIATIONS);
+        Review { activities, deviations, planned, kept }
+    }
+
+    pub fn print(&self) {
+        if self.planned == 0 {
+            println!("Nothing was planned on these days. Plan a day with 'edit --plan' or 'tui --plan'.");
+            return;
+        }
+        for review in &self.activities {
+            println!(
+                "{}: planned {} h, actual {} h{}",
+                review.activity.name.color(review.activity.color()),
+                hours(review.planned),
+                hours(review.actual),
+                review.adherence().map_or(String::new(), |a| format!(", adherence {:.0}%", a))
+            );
+        }
+        println!(
+            "Adherence: {:.0}% ({} of {} planned hours)",
+            self.kept as f32 / self.planned as f32 * 100.,
+            hours(self.kept),
+            hours(self.planned)
+        );
+        if !self.deviations.is_empty() {
+            println!("Largest deviations:");
+        }
+        for deviation in &self.deviations {
+            println!(
+                " - {} {}-{}: planned {}, {}",
+                deviation.date,
+                deviation.start,
+                deviation.end,
+                deviation.planned.name.color(deviation.planned.color()),
+                deviation
+                    .actual
+                    .map_or("nothing recorded".bold().to_string(), |a| format!("did {}", a.name.color(a.color())))
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::Settings;
+
+    fn date(text: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+    }
+
+    fn set(slots: &mut [Option<Activity>], activity: &Activity, from: (usize, usize), to: (usize, usize)) {
+        let (from, to) = (*Slot::from_time(from.0, from.1), *Slot::from_time(to.0, to.1));
+        slots[from..to].fill(Some(activity.clone()));
+    }
+
+    fn days(settings: &Settings) -> Vec<(NaiveDate, Day)> {
+        let [work, email, lunch] = &settings.activities[..] else { unreachable!() };
+        let mut monday = Day::default();
+        set(&mut monday.planned, work, (9, 0), (12, 0));
+        set(&mut monday.planned, lunch, (12, 0), (13, 0));
+        set(&mut monday.time_slots, work, (9, 0), (12, 0));
+        set(&mut monday.time_slots, email, (10, 0), (11, 0));
+        set(&mut monday.time_slots, lunch, (12, 30), (13, 0));
+        set(&mut monday.time_slots, email, (13, 0), (14, 0));
+        let mut unplanned = Day::default();
+        set(&mut unplanned.time_slots, work, (9, 0), (17, 0));
+        let mut wednesday = Day::default();
+        set(&mut wednesday.planned, work, (9, 0), (10, 0));
+        vec![(date("2025-03-24"), monday), (date("2025-03-25"), unplanned), (date("2025-03-26"), wednesday)]
+    }
+
+    #[test]
+    fn compares_planned_and_recorded_slots_of_planned_days() {
+        let settings = Settings::with_activities(&["Work", "Email", "Lunch"]);
+        let days = days(&settings);
+        let review = Review::new(&days);
+
+        let activities = review
+            .activities
+            .iter()
+            .map(|a| (a.activity.name.as_str(), a.planned, a.actual, a.kept, a.adherence()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            activities,
+            vec![
+                ("Work", 16, 8, 8, Some(50.)),
+                ("Email", 0, 8, 0, None),
+                ("Lunch", 4, 2, 2, Some(50.)),
+            ]
+        );
+        assert_eq!((review.planned, review.kept), (20, 10));
+    }
+
+    #[test]
+    fn lists_the_longest_deviations_first() {
+        let settings = Settings::with_activities(&["Work", "Email", "Lunch"]);
+        let days = days(&settings);
+        let review = Review::new(&days);
+
+        let deviations = review
+            .deviations
+            .iter()
+            .map(|d| (d.date, d.start, d.end, d.planned.name.as_str(), d.actual.map(|a| a.name.as_str())))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            deviations,
+            vec![
+                (date("2025-03-24"), Slot::from_time(10, 0), Slot::from_time(11, 0), "Work", Some("Email")),
+                (date("2025-03-26"), Slot::from_time(9, 0), Slot::from_time(10, 0), "Work", None),
+                (date("2025-03-24"), Slot::from_time(12, 0), Slot::from_time(12, 30), "Lunch", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_only_the_largest_deviations() {
+        let settings = Settings::with_activities(&["Work"]);
+        let mut day = Day::default();
+        for slot in (0..14).step_by(2) {
+            day.planned[slot] = Some(settings.activities[0].clone());
+        }
+        let days = vec![(date("2025-03-24"), day)];
+        let review = Review::new(&days);
+        assert_eq!(review.deviations.len(), DEVIATIONS);
+        assert_eq!((review.planned, review.kept), (7, 0));
+    }
+
+    #[test]
+    fn ignores_days_without_a_plan() {
+        let settings = Settings::with_activities(&["Work", "Email", "Lunch"]);
+        let days = days(&settings)[1..2].to_vec();
+        let review = Review::new(&days);
+        assert!(review.activities.is_empty());
+        assert!(review.deviations.is_empty());
+        assert_eq!(review.planned, 0);
+    }
+}
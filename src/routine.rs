@@ -0,0 +1,181 @@
+use chrono::{Datelike, NaiveDate, NaiveTime, Timelike, Weekday};
+use serde_derive::{Deserialize, Serialize};
+use crate::activity::Activity;
+use crate::balance::default_workdays;
+use crate::day::{Day, Slot};
+use crate::settings::Settings;
+use crate::DAY_SLOTS;
+
+/// Something that happens at the same time on certain weekdays, like a
+/// commute or lunch.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Routine {
+    pub activity: String,
+    pub from: NaiveTime,
+    /// May be on the next day, the routine then lasts until the end of the
+    /// day.
+    pub to: NaiveTime,
+    #[serde(default = "default_workdays")]
+    pub weekdays: Vec<Weekday>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    /// Fill it in as soon as the day is created, instead of asking when the
+    /// time is recorded.
+    #[serde(default)]
+    pub auto: bool,
+}
+
+impl Routine {
+    pub fn applies(&self, date: NaiveDate) -> bool {
+        self.weekdays.contains(&date.weekday())
+    }
+
+    pub fn start(&self) -> Slot {
+        Slot::from_time(self.from.hour() as usize, self.from.minute() as usize)
+    }
+
+    pub fn end(&self) -> Slot {
+        let end = Slot::from_time(self.to.hour() as usize, self.to.minute() as usize);
+        if *end <= *self.start() {
+            Slot(DAY_SLOTS)
+        } else {
+            end
+        }
+    }
+
+    /// The activity to record, marked as coming from a routine.
+    pub fn activity(&self, settings: &Settings) -> Option<Activity> {
+        let mut activity = Activity::get_by_name(&settings.activities, &self.activity)?;
+        activity.comment = self.comment.clone();
+        activity.routine = true;
+        Some(activity)
+    }
+
+    /// The empty slots of `day` that the routine covers between `start` and
+    /// `end`.
+    pub fn empty_slots(&self, day: &Day, start: Slot, end: Slot) -> Vec<usize> {
+        ((*self.start()).max(*start)..(*self.end()).min(*end))
+            .filter(|s| day.time_slots[*s].is_none())
+            .collect()
+    }
+
+    /// Record the routine in the empty slots between `start` and `end`, with
+    /// the comment on the first of them. Returns whether anything was filled.
+    pub fn fill(&self, settings: &Settings, day: &mut Day, start: Slot, end: Slot) -> bool {
+        let Some(activity) = self.activity(settings) else {
+            return false;
+        };
+        let slots = self.empty_slots(day, start, end);
+        for (i, slot) in slots.iter().enumerate() {
+            let mut activity = activity.clone();
+            if i > 0 {
+                activity.comment = None;
+            }
+            day.time_slots[*slot] = Some(activity);
+        }
+        !slots.is_empty()
+    }
+}
+
+/// Fill the routines that are filled without asking into a new day.
+pub fn fill_auto(settings: &Settings, date: NaiveDate, day: &mut Day) {
+    for routine in settings.routines.iter().filter(|r| r.auto && r.applies(date)) {
+        routine.fill(settings, day, Slot(0), Slot(DAY_SLOTS));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(text: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+    }
+
+    fn settings(routines: &str) -> Settings {
+        let mut settings = Settings::with_activities(&["Commute", "Lunch", "Sleep"]);
+        settings.routines = toml::from_str::<Config>(routines).unwrap().routines;
+        settings
+    }
+
+    #[derive(serde_derive::Deserialize)]
+    struct Config {
+        routines: Vec<Routine>,
+    }
+
+    fn recorded(day: &Day) -> Vec<(Slot, Slot, String)> {
+        day.slots_collapsed()
+            .filter_map(|(start, end, activity)| Some((start, end, activity?.name)))
+            .collect()
+    }
+
+    #[test]
+    fn lasts_until_the_end_of_the_day_if_it_ends_on_the_next() {
+        let settings = settings(
+            "[[routines]]\nactivity = \"Sleep\"\nfrom = \"23:00:00\"\nto = \"01:00:00\"\n\
+             [[routines]]\nactivity = \"Sleep\"\nfrom = \"23:00:00\"\nto = \"07:00:00\"",
+        );
+        assert_eq!(*settings.routines[0].start(), *Slot::from_time(23, 0));
+        assert_eq!(*settings.routines[0].end(), *Slot::from_time(1, 0));
+        assert_eq!(*settings.routines[1].end(), DAY_SLOTS);
+    }
+
+    #[test]
+    fn applies_on_workdays_unless_configured() {
+        let settings = settings(
+            "[[routines]]\nactivity = \"Commute\"\nfrom = \"08:00:00\"\nto = \"08:30:00\"\n\
+             [[routines]]\nactivity = \"Lunch\"\nfrom = \"12:00:00\"\nto = \"12:30:00\"\nweekdays = [\"Sat\"]",
+        );
+        // 2025-03-28 is a Friday.
+        assert!(settings.routines[0].applies(date("2025-03-28")));
+        assert!(!settings.routines[0].applies(date("2025-03-29")));
+        assert!(!settings.routines[1].applies(date("2025-03-28")));
+        assert!(settings.routines[1].applies(date("2025-03-29")));
+    }
+
+    #[test]
+    fn fills_only_empty_slots_in_the_span_with_the_comment_on_the_first() {
+        let settings = settings(
+            "[[routines]]\nactivity = \"Lunch\"\nfrom = \"12:00:00\"\nto = \"13:00:00\"\ncomment = \"Canteen\"",
+        );
+        let routine = &settings.routines[0];
+        let mut day = Day::default();
+        day.time_slots[*Slot::from_time(12, 0)] = Some(settings.activities[0].clone());
+        assert_eq!(
+            routine.empty_slots(&day, Slot(0), Slot::from_time(12, 30)),
+            vec![*Slot::from_time(12, 15)]
+        );
+
+        assert!(routine.fill(&settings, &mut day, Slot(0), Slot(DAY_SLOTS)));
+        let first = day.time_slots[*Slot::from_time(12, 15)].as_ref().unwrap();
+        assert_eq!(first.name, "Lunch");
+        assert_eq!(first.comment.as_deref(), Some("Canteen"));
+        assert!(first.routine);
+        assert_eq!(day.time_slots[*Slot::from_time(12, 30)].as_ref().unwrap().comment, None);
+        assert_eq!(day.time_slots[*Slot::from_time(12, 0)].as_ref().unwrap().name, "Commute");
+
+        assert!(!routine.fill(&settings, &mut day, Slot(0), Slot(DAY_SLOTS)));
+    }
+
+    #[test]
+    fn does_not_fill_unknown_activities() {
+        let settings = settings("[[routines]]\nactivity = \"Gym\"\nfrom = \"18:00:00\"\nto = \"19:00:00\"");
+        let mut day = Day::default();
+        assert!(!settings.routines[0].fill(&settings, &mut day, Slot(0), Slot(DAY_SLOTS)));
+        assert!(recorded(&day).is_empty());
+    }
+
+    #[test]
+    fn fills_automatic_routines_into_new_days() {
+        let settings = settings(
+            "[[routines]]\nactivity = \"Commute\"\nfrom = \"08:00:00\"\nto = \"08:30:00\"\nauto = true\n\
+             [[routines]]\nactivity = \"Lunch\"\nfrom = \"12:00:00\"\nto = \"12:30:00\"",
+        );
+        let friday = Day::load(&settings, date("2025-03-28"));
+        assert_eq!(
+            recorded(&friday),
+            vec![(Slot::from_time(8, 0), Slot::from_time(8, 30), "Commute".to_string())]
+        );
+        assert!(recorded(&Day::load(&settings, date("2025-03-29"))).is_empty());
+    }
+}
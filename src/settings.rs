@@ -1,9 +1,17 @@
 use std::path::PathBuf;
 use std::cell::RefCell;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer};
 use serde_derive::{Deserialize, Serialize};
-use chrono::{Datelike, Duration, Local};
+use chrono::{Datelike, Duration, Local, NaiveDate};
 use crate::{DAY_START, SLOTS_PER_HOUR};
 use crate::activity::Activity;
+use crate::balance::BalanceSettings;
+use crate::billing::Client;
+use crate::compliance::ComplianceSettings;
+use crate::day::Rounding;
+use crate::reminder::ReminderSettings;
+use crate::routine::Routine;
 
 type Shortcuts = Vec<Option<char>>;
 
@@ -14,11 +22,51 @@ pub struct Settings {
     pub data_dir: PathBuf,
     pub git_repos_dir: PathBuf,
     pub git_author: String,
+    /// Called as `<svg_converter> <svg> -o <png>` to render charts as PNG.
+    #[serde(default = "default_svg_converter")]
+    pub svg_converter: String,
+    /// How times entered in prompts and flags are rounded to slots.
+    #[serde(default = "default_time_rounding")]
+    pub time_rounding: Rounding,
+    #[serde(deserialize_with = "deserialize_activities")]
     pub activities: Vec<Activity>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub clients: Vec<Client>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub routines: Vec<Routine>,
+    #[serde(default)]
+    pub balance: Option<BalanceSettings>,
+    #[serde(default)]
+    pub compliance: Option<ComplianceSettings>,
+    #[serde(default)]
+    pub reminder: Option<ReminderSettings>,
     #[serde(skip)]
     shortcuts: RefCell<Option<Shortcuts>>,
 }
 
+fn default_svg_converter() -> String {
+    "rsvg-convert".to_string()
+}
+
+fn default_time_rounding() -> Rounding {
+    Rounding::Down
+}
+
+/// The activities, rejecting a shortcut that is configured for two of them.
+fn deserialize_activities<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Activity>, D::Error> {
+    let activities = Vec::<Activity>::deserialize(deserializer)?;
+    for (i, activity) in activities.iter().enumerate() {
+        let Some(shortcut) = activity.shortcut else { continue };
+        if let Some(other) = activities[..i].iter().find(|a| a.shortcut == Some(shortcut)) {
+            return Err(D::Error::custom(format!(
+                "shortcut '{}' is configured for both {} and {}",
+                shortcut, other.name, activity.name
+            )));
+        }
+    }
+    Ok(activities)
+}
+
 impl Default for Settings {
     fn default() -> Self {
         Settings {
@@ -28,6 +76,13 @@ impl Default for Settings {
             git_author: "Your Name".to_string(),
             data_dir: crate::get_base_dirs().data_dir().into(),
             activities: vec![],
+            svg_converter: default_svg_converter(),
+            time_rounding: default_time_rounding(),
+            clients: vec![],
+            routines: vec![],
+            balance: None,
+            compliance: None,
+            reminder: None,
             shortcuts: RefCell::new(None),
         }
     }
@@ -39,18 +94,14 @@ impl Settings {
         self.get_shortcuts()[index]
     }
 
+    /// The shortcut of each activity: the one from the config or else the
+    /// first character of its name that no other activity uses.
     pub fn get_shortcuts(&self) -> Shortcuts {
         if self.shortcuts.borrow().is_none() {
-            let mut shortcuts = Vec::with_capacity(self.activities.len());
-            for activity in &self.activities {
-                if let Some(chr) = activity
-                    .name
-                    .chars()
-                    .find(|c| !shortcuts.contains(&Some(*c)))
-                {
-                    shortcuts.push(Some(chr))
-                } else {
-                    shortcuts.push(None)
+            let mut shortcuts: Shortcuts = self.activities.iter().map(|a| a.shortcut).collect();
+            for (i, activity) in self.activities.iter().enumerate() {
+                if shortcuts[i].is_none() {
+                    shortcuts[i] = activity.name.chars().find(|c| !shortcuts.contains(&Some(*c)));
                 }
             }
             *self.shortcuts.borrow_mut() = Some(shortcuts);
@@ -58,6 +109,10 @@ impl Settings {
         self.shortcuts.borrow().as_ref().unwrap().clone()
     }
 
+    pub fn get_client(&self, name: &str) -> Option<&Client> {
+        self.clients.iter().find(|c| c.name.eq_ignore_ascii_case(name))
+    }
+
     pub fn get_filename_today(&self) -> PathBuf {
         let time = Local::now() - Duration::hours((*DAY_START / SLOTS_PER_HOUR) as i64);
         self.get_filename_by_date(
@@ -67,8 +122,76 @@ impl Settings {
         )
     }
 
+    pub fn get_filename_by_day(&self, date: NaiveDate) -> PathBuf {
+        self.get_filename_by_date(
+            date.year() as usize,
+            date.month() as usize,
+            date.day() as usize,
+        )
+    }
+
     pub fn get_filename_by_date(&self, year: usize, month: usize, day: usize) -> PathBuf {
         self.data_dir
             .join(format!("{}-{}-{}.json", year, month, day))
     }
 }
+
+#[cfg(test)]
+impl Settings {
+    /// Settings with the given productive activities and a data directory
+    /// that does not exist, so nothing is read from disk.
+    pub fn with_activities(names: &[&str]) -> Self {
+        Settings {
+            data_dir: PathBuf::from("/nonexistent/time-tracker"),
+            activities: names
+                .iter()
+                .map(|name| Activity {
+                    name: name.to_string(),
+                    productive: true,
+                    comment: None,
+                    tags: vec![],
+                    shortcut: None,
+                    routine: false,
+                })
+                .collect(),
+            ..Settings::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(activities: &str) -> Result<Settings, toml::de::Error> {
+        toml::from_str(&format!(
+            "editor = \"vim\"\ngit = \"git\"\ndata_dir = \"/tmp\"\ngit_repos_dir = \"/tmp\"\ngit_author = \"Me\"\n{}",
+            activities
+        ))
+    }
+
+    #[test]
+    fn derives_shortcuts_the_config_leaves_free() {
+        let settings = parse(
+            "[[activities]]\nname = \"Work\"\nproductive = true\n\
+             [[activities]]\nname = \"Walk\"\nproductive = false\nshortcut = \"W\"",
+        )
+        .unwrap();
+        assert_eq!(settings.get_shortcuts(), vec![Some('o'), Some('W')]);
+    }
+
+    #[test]
+    fn rejects_duplicate_shortcuts() {
+        let error = parse(
+            "[[activities]]\nname = \"Work\"\nproductive = true\nshortcut = \"w\"\n\
+             [[activities]]\nname = \"Walk\"\nproductive = false\nshortcut = \"w\"",
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("shortcut 'w' is configured for both Work and Walk"), "{}", error);
+        assert!(parse(
+            "[[activities]]\nname = \"Work\"\nproductive = true\nshortcut = \"w\"\n\
+             [[activities]]\nname = \"Walk\"\nproductive = false\nshortcut = \"W\"",
+        )
+        .is_ok());
+    }
+}
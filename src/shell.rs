@@ -0,0 +1,198 @@
+use clap::Parser;
+use colored::Colorize;
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+use crate::cli::{self, Cli, Commands};
+use crate::{get_day, UI};
+
+pub const HISTORY_FILENAME: &str = "shell_history.txt";
+
+/// Split a line into words like a shell would, keeping quoted text together.
+fn split_words(line: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote = None;
+    for c in line.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => word.get_or_insert_with(String::new).push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            },
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            (None, c) => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if quote.is_some() {
+        return Err("Unterminated quote.".to_string());
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// Run one line of the shell. Returns false to leave it.
+fn run_line(ui: &mut UI, words: Vec<String>) -> Result<bool, String> {
+    match words[0].as_str() {
+        "exit" | "quit" => return Ok(false),
+        "date" if words.len() == 1 => println!("{}", ui.date),
+        "date" => {
+            let text = words[1..].join(" ");
+            ui.switch_day(get_day(&text).ok_or_else(|| format!("Invalid date: {}", text))?);
+            println!("{} {}", "Switched to".bright_blue(), ui.date);
+        },
+        _ => {
+            let args = cli::expand_shorthand(std::iter::once("time-tracker".to_string()).chain(words));
+            match Cli::try_parse_from(args) {
+                Ok(Cli { command: Some(Commands::Shell), .. }) => println!("This is the shell already."),
+                Ok(cli) => {
+                    let result = crate::run(cli, ui);
+                    // Some commands write the data files directly.
+                    ui.reload();
+                    result?;
+                },
+                Err(error) => error.print().expect("print"),
+            }
+        },
+    }
+    Ok(true)
+}
+
+/// `shell`: Read commands in a loop and run them on the same `UI`, until
+/// `exit` or Ctrl-D. `date DAY` switches the day they work on.
+pub fn run(ui: &mut UI) -> Result<(), String> {
+    let mut editor = DefaultEditor::new().map_err(|e| e.to_string())?;
+    let history = ui.settings.data_dir.join(HISTORY_FILENAME);
+    // There is no history the first time.
+    let _ = editor.load_history(&history);
+    println!("Enter commands like 'today', 'add Work 9-11' or '3s', 'date DAY' to switch the day, 'help' or 'exit'.");
+    loop {
+        let line = match editor.readline(&format!("{}> ", ui.date)) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(error) => return Err(error.to_string()),
+        };
+        let words = match split_words(&line) {
+            Ok(words) if words.is_empty() => continue,
+            Ok(words) => words,
+            Err(error) => {
+                eprintln!("{}", error.red());
+                continue;
+            },
+        };
+        editor.add_history_entry(line.as_str()).map_err(|e| e.to_string())?;
+        match run_line(ui, words) {
+            Ok(true) => {},
+            Ok(false) => break,
+            Err(error) => eprintln!("{}", error.red()),
+        }
+    }
+    editor.save_history(&history).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate};
+    use super::*;
+    use crate::day::Day;
+    use crate::get_today;
+    use crate::settings::Settings;
+
+    fn words(line: &str) -> Vec<String> {
+        split_words(line).unwrap()
+    }
+
+    #[test]
+    fn splits_words_at_whitespace() {
+        assert_eq!(words("add Work 9-11"), ["add", "Work", "9-11"]);
+        assert_eq!(words("  today\t "), ["today"]);
+        assert!(words("").is_empty());
+        assert!(words("   ").is_empty());
+    }
+
+    #[test]
+    fn keeps_quoted_text_together() {
+        assert_eq!(
+            words("add Work 9-11 --comment \"code review\""),
+            ["add", "Work", "9-11", "--comment", "code review"]
+        );
+        assert_eq!(words("date 'last friday'"), ["date", "last friday"]);
+        assert_eq!(words("comment \"it's done\""), ["comment", "it's done"]);
+        assert_eq!(words("comment 'say \"hi\"'"), ["comment", "say \"hi\""]);
+        assert_eq!(words("comment --at=\"14:00\"x"), ["comment", "--at=14:00x"]);
+        assert_eq!(words("comment \"\" x"), ["comment", "", "x"]);
+    }
+
+    #[test]
+    fn rejects_unterminated_quotes() {
+        assert_eq!(split_words("comment \"code review"), Err("Unterminated quote.".to_string()));
+        assert_eq!(split_words("date 'yesterday"), Err("Unterminated quote.".to_string()));
+    }
+
+    fn ui(settings: &Settings) -> UI<'_> {
+        UI {
+            day: Day::default(),
+            date: get_today(),
+            file: settings.get_filename_by_day(get_today()),
+            settings,
+        }
+    }
+
+    fn run(ui: &mut UI, line: &str) -> Result<bool, String> {
+        run_line(ui, words(line))
+    }
+
+    #[test]
+    fn leaves_on_exit_and_quit() {
+        let settings = Settings::with_activities(&["Work"]);
+        let mut ui = ui(&settings);
+        assert_eq!(run(&mut ui, "exit"), Ok(false));
+        assert_eq!(run(&mut ui, "quit"), Ok(false));
+        assert_eq!(run(&mut ui, "path"), Ok(true));
+        assert_eq!(run(&mut ui, "shell"), Ok(true));
+    }
+
+    #[test]
+    fn switches_the_day() {
+        let settings = Settings::with_activities(&["Work"]);
+        let mut ui = ui(&settings);
+        assert_eq!(run(&mut ui, "date yesterday"), Ok(true));
+        assert_eq!(ui.date, get_today() - Duration::days(1));
+        assert_eq!(ui.file, settings.get_filename_by_day(ui.date));
+        assert_eq!(run(&mut ui, "date 2025-03-24"), Ok(true));
+        assert_eq!(ui.date, NaiveDate::from_ymd_opt(2025, 3, 24).unwrap());
+        assert_eq!(run(&mut ui, "date"), Ok(true));
+        assert_eq!(run(&mut ui, "date someday"), Err("Invalid date: someday".to_string()));
+        assert_eq!(ui.date, NaiveDate::from_ymd_opt(2025, 3, 24).unwrap());
+    }
+
+    #[test]
+    fn rejects_commands_about_now_on_other_days() {
+        let settings = Settings::with_activities(&["Work"]);
+        let mut ui = ui(&settings);
+        run(&mut ui, "date yesterday").unwrap();
+        for line in ["today", "3s", "until", "split 11:00 Work Work", "status"] {
+            let error = run(&mut ui, line).unwrap_err();
+            assert!(error.contains("only works on today"), "{}: {}", line, error);
+        }
+        assert!(ui.day.time_slots.iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn looks_back_from_the_day_of_the_shell() {
+        let settings = Settings::with_activities(&["Work"]);
+        let mut ui = ui(&settings);
+        run(&mut ui, "date 2025-03-24").unwrap();
+        assert_eq!(run(&mut ui, "yesterday"), Err("Nothing was recorded on 2025-03-23.".to_string()));
+    }
+
+    #[test]
+    fn reports_invalid_commands_without_leaving() {
+        let settings = Settings::with_activities(&["Work"]);
+        let mut ui = ui(&settings);
+        assert_eq!(run(&mut ui, "no-such-command"), Ok(true));
+        assert_eq!(run(&mut ui, "add Work"), Ok(true));
+        assert_eq!(run(&mut ui, "add Nothing 9-10"), Err("Unknown activity: Nothing".to_string()));
+    }
+}
@@ -0,0 +1,112 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde_json::json;
+use crate::activity::Activity;
+use crate::day::{Day, Slot};
+use crate::report::escape;
+use crate::settings::Settings;
+use crate::{timer, PRODUCTIVE_TARGET, SLOTS_PER_HOUR};
+
+/// What is shown in a status bar.
+pub struct Status {
+    /// The running timer's activity, or else the last one.
+    pub activity: Option<Activity>,
+    pub running: bool,
+    /// Minutes since the timer was started, or since the last activity ended.
+    pub minutes: i64,
+    /// Empty slots between the first activity and now, in minutes.
+    pub untracked: usize,
+    pub hours: f32,
+    pub target: f32,
+}
+
+fn duration(minutes: i64) -> String {
+    format!("{}:{:02}", minutes / 60, minutes % 60)
+}
+
+impl Status {
+    pub fn new(settings: &Settings, date: NaiveDate, day: &Day, now: NaiveDateTime) -> Self {
+        let (_, slot) = Slot::from_datetime(now);
+        let timer = timer::read(settings);
+        let last = day.entry_before(slot);
+        let (activity, minutes) = if let Some(timer) = timer.as_ref() {
+            (Some(timer.activity.clone()), (now - timer.since).num_minutes())
+        } else if let Some((end, activity)) = last {
+            (Some(activity.clone()), (now - end.next().to_datetime(date)).num_minutes())
+        } else {
+            (None, 0)
+        };
+        let untracked = day.first_non_empty().map_or(0, |first| {
+            day.time_slots[*first..(*slot).max(*first)].iter().filter(|o| o.is_none()).count()
+        });
+        Status {
+            activity,
+            running: timer.is_some(),
+            minutes: minutes.max(0),
+            untracked: untracked * 60 / SLOTS_PER_HOUR,
+            hours: day.hours_productive(),
+            target: settings.balance.as_ref().map_or(PRODUCTIVE_TARGET, |b| b.target(date)),
+        }
+    }
+
+    fn since(&self) -> String {
+        if self.running {
+            format!("▶ {}", duration(self.minutes))
+        } else {
+            format!("{} ago", duration(self.minutes))
+        }
+    }
+
+    fn rest(&self) -> String {
+        let mut rest = format!("{}/{} h", self.hours, self.target);
+        if self.untracked > 0 {
+            rest = format!("{}m untracked · {}", self.untracked, rest);
+        }
+        rest
+    }
+
+    pub fn plain(&self) -> String {
+        match &self.activity {
+            Some(activity) => format!("{} {} · {}", activity.name, self.since(), self.rest()),
+            None => format!("nothing recorded · {}", self.rest()),
+        }
+    }
+
+    /// With tmux style tags for the color of the activity.
+    pub fn tmux(&self) -> String {
+        match &self.activity {
+            Some(activity) => format!(
+                "#[fg={}]{}#[default] {} · {}",
+                activity.color(),
+                activity.name,
+                self.since(),
+                self.rest()
+            ),
+            None => format!("nothing recorded · {}", self.rest()),
+        }
+    }
+
+    /// JSON for a waybar custom module, with pango markup for the color.
+    pub fn waybar(&self) -> String {
+        let text = match &self.activity {
+            Some(activity) => format!(
+                "<span color='{}'>{}</span> {} · {}",
+                activity.css_color(),
+                escape(&activity.name),
+                self.since(),
+                self.rest()
+            ),
+            None => format!("nothing recorded · {}", self.rest()),
+        };
+        let class = self
+            .activity
+            .as_ref()
+            .map_or("none".to_string(), |a| a.name.to_lowercase().replace(' ', "-"));
+        json!({
+            "text": text,
+            "tooltip": format!("Hours Productive: {} of {}\nUntracked: {} minutes", self.hours, self.target, self.untracked),
+            "class": class,
+            "percentage": (self.hours / self.target.max(1.) * 100.).min(100.) as u32,
+        })
+        .to_string()
+    }
+}
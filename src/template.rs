@@ -0,0 +1,161 @@
+//! Templates are saved days (or parts of days) in the same format as the data
+//! files, in a directory next to them.
+
+use std::fs;
+use std::path::PathBuf;
+use colored::Colorize;
+use crate::activity::Activity;
+use crate::day::{Day, Slot};
+use crate::record::parse_range;
+use crate::settings::Settings;
+use crate::{get_input, DAY_SLOTS, UI};
+
+pub const TEMPLATES_DIRNAME: &str = "templates";
+
+fn get_filename(settings: &Settings, name: &str) -> Result<PathBuf, String> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err(format!("Invalid template name: {}", name));
+    }
+    Ok(settings.data_dir.join(TEMPLATES_DIRNAME).join(format!("{}.json", name)))
+}
+
+/// Names of the saved templates.
+pub fn names(settings: &Settings) -> Vec<String> {
+    let mut names = fs::read_dir(settings.data_dir.join(TEMPLATES_DIRNAME))
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().to_str()?.strip_suffix(".json").map(str::to_string))
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+/// A template of the recorded slots of `day` between `start` and `end`,
+/// without what was planned, focus sessions or routine marks.
+fn from_day(day: &Day, start: Slot, end: Slot) -> Day {
+    let mut template = Day::default();
+    for slot in *start..*end {
+        template.time_slots[slot] = day.time_slots[slot].clone().map(|activity| Activity {
+            routine: false,
+            ..activity
+        });
+    }
+    template
+}
+
+/// `save-template NAME [START-END]`: Save the current day, or only the given
+/// span of it, as a template. Replaces a template of the same name.
+pub fn save(ui: &UI, name: &str, range: Option<&str>) -> Result<(), String> {
+    let file = get_filename(ui.settings, name)?;
+    let (start, end) = match range {
+        Some(range) => parse_range(ui, range)?,
+        None => (Slot(0), Slot(DAY_SLOTS)),
+    };
+    let template = from_day(&ui.day, start, end);
+    if template.first_non_empty().is_none() {
+        return Err("There is nothing to save.".to_string());
+    }
+    fs::create_dir_all(file.parent().unwrap()).map_err(|e| e.to_string())?;
+    template.write(&file);
+    println!("{} {:?}", "Saved template".bright_blue(), file);
+    Ok(())
+}
+
+/// `apply-template NAME`: Copy the entries of a template into the empty
+/// slots of the current day. Slots that are recorded differently already are
+/// listed and kept, and have to be confirmed unless `yes`.
+pub fn apply(ui: &mut UI, name: &str, yes: bool) -> Result<(), String> {
+    let file = get_filename(ui.settings, name)?;
+    if !file.exists() {
+        return Err(format!("Unknown template: {} (saved: {})", name, names(ui.settings).join(", ")));
+    }
+    let template = Day::read(&file);
+    let mut conflicts: Vec<(Slot, Slot, &Activity, &Activity)> = Vec::new();
+    for (start, end, activity) in template.entries() {
+        for slot in *start..*end {
+            let Some(recorded) = ui.day.time_slots[slot].as_ref().filter(|a| a != &activity) else {
+                continue;
+            };
+            match conflicts.last_mut() {
+                Some((_, end, last, template)) if **end == slot && *last == recorded && *template == activity => {
+                    *end = Slot(slot + 1);
+                },
+                _ => conflicts.push((Slot(slot), Slot(slot + 1), recorded, activity)),
+            }
+        }
+    }
+    if !conflicts.is_empty() {
+        println!("{}", "These slots are recorded already and will be kept:".bright_yellow());
+        for (start, end, recorded, activity) in &conflicts {
+            println!(" - {}-{} - {} (template: {})", start, end, recorded, activity);
+        }
+        if !yes {
+            println!("Apply the template to the remaining slots? (y/n)");
+            if get_input::<String>().as_deref() != Some("y") {
+                return Ok(());
+            }
+        }
+    }
+    let mut filled = 0;
+    for (slot, activity) in template.time_slots.into_iter().enumerate() {
+        if activity.is_some() && ui.day.time_slots[slot].is_none() {
+            ui.day.time_slots[slot] = activity;
+            filled += 1;
+        }
+    }
+    if filled == 0 {
+        println!("There are no empty slots to fill.");
+        return Ok(());
+    }
+    println!("Filled {} slot(s) on {} from {}.", filled, ui.date, name);
+    ui.save();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_today;
+
+    #[test]
+    fn saves_only_the_recorded_slots_in_the_range() {
+        let mut settings = Settings::with_activities(&["Work", "Lunch"]);
+        settings.data_dir = std::env::temp_dir().join(format!("time-tracker-template-{}", std::process::id()));
+        let date = get_today();
+        let mut day = Day::default();
+        for slot in *Slot::from_time(9, 0)..*Slot::from_time(13, 0) {
+            day.time_slots[slot] = Some(settings.activities[0].clone());
+            day.planned[slot] = Some(settings.activities[1].clone());
+        }
+        day.time_slots[*Slot::from_time(12, 0)].as_mut().unwrap().routine = true;
+        day.focus_sessions = 2;
+        let ui = UI {
+            day,
+            date,
+            file: settings.get_filename_by_day(date),
+            settings: &settings,
+        };
+
+        save(&ui, "morning", Some("11-12:30")).unwrap();
+        let template = Day::read(&get_filename(&settings, "morning").unwrap());
+        fs::remove_dir_all(&settings.data_dir).unwrap();
+
+        assert_eq!(
+            template.entries().into_iter().map(|(start, end, a)| (start, end, a.name.clone())).collect::<Vec<_>>(),
+            vec![(Slot::from_time(11, 0), Slot::from_time(12, 30), "Work".to_string())]
+        );
+        assert!(template.time_slots.iter().flatten().all(|a| !a.routine));
+        assert!(!template.is_planned());
+        assert_eq!(template.focus_sessions, 0);
+    }
+
+    #[test]
+    fn rejects_names_that_are_not_plain_file_names() {
+        let settings = Settings::with_activities(&["Work"]);
+        for name in ["", "../day", "a/b", ".hidden"] {
+            assert!(get_filename(&settings, name).is_err(), "{:?}", name);
+        }
+        assert!(get_filename(&settings, "workday").is_ok());
+    }
+}
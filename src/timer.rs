@@ -0,0 +1,161 @@
+use std::fs;
+use std::path::PathBuf;
+use chrono::{Local, NaiveDateTime};
+use colored::Colorize;
+use serde_derive::{Deserialize, Serialize};
+use crate::activity::Activity;
+use crate::day::{Day, Slot};
+use crate::settings::Settings;
+use crate::{parse, range, DAY_SLOTS};
+
+pub const TIMER_FILENAME: &str = "timer.json";
+
+/// An activity that is running since some time. Its slots are only filled
+/// when it is stopped.
+#[derive(Serialize, Deserialize)]
+pub struct Timer {
+    pub activity: Activity,
+    pub since: NaiveDateTime,
+}
+
+impl Timer {
+    /// Time since the timer was started, like `1:05`.
+    pub fn elapsed(&self, now: NaiveDateTime) -> String {
+        let minutes = (now - self.since).num_minutes().max(0);
+        format!("{}:{:02}", minutes / 60, minutes % 60)
+    }
+}
+
+/// Fill the slots from `since` until `until` with the activity, in the data
+/// files of several days if the time span crosses `DAY_START`. The comment of
+/// the activity goes to the first slot of each day.
+pub fn record(settings: &Settings, activity: &Activity, since: NaiveDateTime, until: NaiveDateTime) {
+    let (start_date, start) = Slot::from_datetime(parse::round(since, settings.time_rounding));
+    let (end_date, end) = Slot::from_datetime(parse::round(until, settings.time_rounding));
+    let mut activity = activity.clone();
+    let comment = activity.comment.take();
+    for date in range::days(start_date, end_date) {
+        let first = if date == start_date { *start } else { 0 };
+        let last = if date == end_date { *end } else { DAY_SLOTS };
+        if first >= last {
+            continue;
+        }
+        let file = settings.get_filename_by_day(date);
+        let mut day = Day::load(settings, date);
+        for slot in first..last {
+            day.time_slots[slot] = Some(activity.clone());
+        }
+        day.time_slots[first].as_mut().unwrap().comment = comment.clone();
+        println!("{} {}-{} - {}", date, Slot(first), Slot(last), day.time_slots[first].as_ref().unwrap());
+        day.write(&file);
+    }
+    if (start_date, *start) >= (end_date, *end) {
+        println!("{}", "Less than one slot, nothing recorded.".bright_yellow());
+    } else {
+        println!("{}", "Saved!".bright_blue());
+    }
+}
+
+fn path(settings: &Settings) -> PathBuf {
+    settings.data_dir.join(TIMER_FILENAME)
+}
+
+/// The running timer, if any.
+pub fn read(settings: &Settings) -> Option<Timer> {
+    let text = fs::read_to_string(path(settings)).ok()?;
+    Some(serde_json::from_str(&text).expect("parse timer"))
+}
+
+fn write(settings: &Settings, timer: &Timer) {
+    fs::write(path(settings), serde_json::to_string(timer).expect("serialize")).expect("write");
+}
+
+/// `start ACTIVITY [COMMENT]`
+pub fn start(settings: &Settings, name: &str, comment: Option<String>) -> Result<(), String> {
+    if let Some(timer) = read(settings) {
+        return Err(format!(
+            "{} is already running since {}, use stop or switch.",
+            timer.activity.name,
+            timer.since.format("%H:%M")
+        ));
+    }
+    let mut activity = Activity::find(settings, name).ok_or_else(|| format!("Unknown activity: {}", name))?;
+    activity.comment = comment;
+    let timer = Timer { activity, since: Local::now().naive_local() };
+    println!("Started {} at {}", timer.activity, timer.since.format("%H:%M"));
+    write(settings, &timer);
+    Ok(())
+}
+
+/// `stop`: Record the running timer.
+pub fn stop(settings: &Settings) -> Result<(), String> {
+    let timer = read(settings).ok_or_else(|| "There is no running timer.".to_string())?;
+    let now = Local::now().naive_local();
+    println!("Stopped {} after {}", timer.activity, timer.elapsed(now));
+    record(settings, &timer.activity, timer.since, now);
+    fs::remove_file(path(settings)).expect("remove file");
+    Ok(())
+}
+
+/// `switch ACTIVITY [COMMENT]`: Stop the running timer, if any, and start
+/// another one.
+pub fn switch(settings: &Settings, name: &str, comment: Option<String>) -> Result<(), String> {
+    Activity::find(settings, name).ok_or_else(|| format!("Unknown activity: {}", name))?;
+    if read(settings).is_some() {
+        stop(settings)?;
+    }
+    start(settings, name, comment)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn date(text: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+    }
+
+    fn time(text: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn entries(settings: &Settings, date: NaiveDate) -> Vec<(Slot, Slot, Option<String>)> {
+        Day::read(&settings.get_filename_by_day(date))
+            .entries()
+            .into_iter()
+            .map(|(start, end, activity)| (start, end, activity.comment.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn records_a_timer_over_midnight_and_the_start_of_the_day() {
+        let mut settings = Settings::with_activities(&["Work"]);
+        settings.data_dir = std::env::temp_dir().join(format!("time-tracker-timer-{}", std::process::id()));
+        fs::create_dir_all(&settings.data_dir).unwrap();
+        let mut activity = settings.activities[0].clone();
+        activity.comment = Some("Release".to_string());
+
+        record(&settings, &activity, time("2025-03-24 22:05"), time("2025-03-25 06:10"));
+        let monday = entries(&settings, date("2025-03-24"));
+        let tuesday = entries(&settings, date("2025-03-25"));
+        let wednesday = settings.get_filename_by_day(date("2025-03-26")).exists();
+        fs::remove_dir_all(&settings.data_dir).unwrap();
+
+        // Until 04:00 the night still belongs to the day it started on.
+        let release = Some("Release".to_string());
+        assert_eq!(monday, vec![(Slot::from_time(22, 0), Slot(DAY_SLOTS), release.clone())]);
+        assert_eq!(tuesday, vec![(Slot::from_time(4, 0), Slot::from_time(6, 0), release)]);
+        assert!(!wednesday);
+    }
+
+    #[test]
+    fn shows_the_elapsed_time() {
+        let timer = Timer {
+            activity: Settings::with_activities(&["Work"]).activities[0].clone(),
+            since: time("2025-03-24 22:05"),
+        };
+        assert_eq!(timer.elapsed(time("2025-03-25 00:10")), "2:05");
+        assert_eq!(timer.elapsed(time("2025-03-24 22:00")), "0:00");
+    }
+}
@@ -0,0 +1,413 @@
+use std::io;
+use std::ops::RangeInclusive;
+use chrono::{Datelike, NaiveDate};
+use ratatui::backend::Backend;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{List, ListItem, ListState, Paragraph};
+use ratatui::{Frame, Terminal};
+use crate::activity::Activity;
+use crate::day::{Day, Slot};
+use crate::settings::Settings;
+use crate::{DAY_SLOTS, SLOTS_PER_HOUR};
+
+const HELP: &str = "↑/↓ move, shift extends, PgUp/PgDn hour, ←/→ day, shortcut fills, \
+                    Del clears, Enter comments, Ctrl-S saves, Esc quits";
+
+/// Full-screen editor for the slots of a day. Keys are handled by `handle`
+/// and the screen is drawn by `draw`, so it works with any backend.
+pub struct Editor<'s> {
+    settings: &'s Settings,
+    pub date: NaiveDate,
+    pub day: Day,
+    /// Edit the planned slots instead of the recorded ones.
+    pub plan: bool,
+    pub cursor: usize,
+    /// The other end of the selection while it is extended with shift.
+    anchor: Option<usize>,
+    /// The comment being entered, if any.
+    comment: Option<String>,
+    modified: bool,
+    message: String,
+    list: ListState,
+    pub quit: bool,
+}
+
+impl<'s> Editor<'s> {
+    pub fn new(settings: &'s Settings, date: NaiveDate, plan: bool) -> Self {
+        let day = Day::load(settings, date);
+        let cursor = day.layer(plan).iter().position(Option::is_some).unwrap_or(0);
+        Editor {
+            settings,
+            date,
+            day,
+            plan,
+            cursor,
+            anchor: None,
+            comment: None,
+            modified: false,
+            message: HELP.to_string(),
+            list: ListState::default(),
+            quit: false,
+        }
+    }
+
+    /// Write the day if it was changed.
+    pub fn save(&mut self) {
+        if self.modified {
+            self.day.write(&self.settings.get_filename_by_day(self.date));
+            self.modified = false;
+            self.message = "Saved!".to_string();
+        }
+    }
+
+    fn switch_day(&mut self, date: Option<NaiveDate>) {
+        if let Some(date) = date {
+            self.save();
+            self.date = date;
+            self.day = Day::load(self.settings, date);
+            self.anchor = None;
+        }
+    }
+
+    /// The selected slots, or just the one under the cursor.
+    pub fn selection(&self) -> RangeInclusive<usize> {
+        let anchor = self.anchor.unwrap_or(self.cursor);
+        anchor.min(self.cursor)..=anchor.max(self.cursor)
+    }
+
+    fn move_cursor(&mut self, offset: isize, extend: bool) {
+        if extend {
+            self.anchor.get_or_insert(self.cursor);
+        } else {
+            self.anchor = None;
+        }
+        self.cursor = (self.cursor as isize + offset).clamp(0, DAY_SLOTS as isize - 1) as usize;
+    }
+
+    fn fill(&mut self, activity: Option<Activity>) {
+        for i in self.selection() {
+            self.day.layer_mut(self.plan)[i] = activity.clone();
+        }
+        self.modified = true;
+        self.anchor = None;
+    }
+
+    fn finish_comment(&mut self, text: String) {
+        let slot = *self.selection().start();
+        if let Some(activity) = self.day.layer_mut(self.plan)[slot].as_mut() {
+            activity.comment = Some(text).filter(|t| !t.is_empty());
+            self.modified = true;
+        } else {
+            self.message = format!("There is no activity at {}.", Slot(slot));
+        }
+    }
+
+    pub fn handle(&mut self, key: KeyEvent) {
+        if let Some(comment) = self.comment.as_mut() {
+            match key.code {
+                KeyCode::Enter => {
+                    let text = self.comment.take().unwrap();
+                    self.finish_comment(text);
+                },
+                KeyCode::Esc => self.comment = None,
+                KeyCode::Backspace => {
+                    comment.pop();
+                },
+                KeyCode::Char(c) => comment.push(c),
+                _ => {},
+            }
+            return;
+        }
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let hour = SLOTS_PER_HOUR as isize;
+        self.message = HELP.to_string();
+        match key.code {
+            KeyCode::Up => self.move_cursor(-1, shift),
+            KeyCode::Down => self.move_cursor(1, shift),
+            KeyCode::PageUp => self.move_cursor(-hour, shift),
+            KeyCode::PageDown => self.move_cursor(hour, shift),
+            KeyCode::Home => self.move_cursor(-(DAY_SLOTS as isize), shift),
+            KeyCode::End => self.move_cursor(DAY_SLOTS as isize, shift),
+            KeyCode::Left => self.switch_day(self.date.pred_opt()),
+            KeyCode::Right => self.switch_day(self.date.succ_opt()),
+            KeyCode::Delete | KeyCode::Backspace => self.fill(None),
+            KeyCode::Enter => {
+                let slot = *self.selection().start();
+                let comment = self.day.layer(self.plan)[slot].as_ref().and_then(|a| a.comment.clone());
+                self.comment = Some(comment.unwrap_or_default());
+            },
+            KeyCode::Esc => {
+                self.save();
+                self.quit = true;
+            },
+            KeyCode::Char('c') if ctrl => {
+                self.save();
+                self.quit = true;
+            },
+            KeyCode::Char('s') if ctrl => {
+                self.save();
+            },
+            KeyCode::Char(c) => {
+                if let Some(activity) = Activity::find(self.settings, &c.to_string()) {
+                    self.fill(Some(activity));
+                } else {
+                    self.message = format!("No activity with the shortcut '{}'.", c);
+                }
+            },
+            _ => {},
+        }
+    }
+
+    fn color(activity: &Activity) -> Color {
+        activity.color().parse().unwrap_or(Color::Gray)
+    }
+
+    fn slot_item(&self, i: usize) -> ListItem<'static> {
+        let mut spans = vec![Span::raw(format!("{} ", Slot(i)))];
+        if let Some(activity) = &self.day.layer(self.plan)[i] {
+            let color = Self::color(activity);
+            spans.push(Span::styled("██ ", Style::default().fg(color)));
+            spans.push(Span::styled(activity.name.clone(), Style::default().fg(color)));
+            for tag in &activity.tags {
+                spans.push(Span::raw(format!(" #{}", tag)));
+            }
+            if let Some(comment) = &activity.comment {
+                spans.push(Span::raw(format!(" - {}", comment)));
+            }
+        } else {
+            spans.push(Span::styled("·", Style::default().fg(Color::DarkGray)));
+        }
+        let item = ListItem::new(Line::from(spans));
+        if self.anchor.is_some() && self.selection().contains(&i) {
+            item.style(Style::default().bg(Color::DarkGray))
+        } else {
+            item
+        }
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame) {
+        let [header, body, footer] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let title = format!(
+            "{}{} {}{} - Hours Productive: {}",
+            if self.plan { "Plan for " } else { "" },
+            self.date.weekday(),
+            self.date,
+            if self.modified { " (modified)" } else { "" },
+            self.day.hours_productive()
+        );
+        frame.render_widget(Paragraph::new(title).style(Style::default().add_modifier(Modifier::BOLD)), header);
+
+        let items = (0..DAY_SLOTS).map(|i| self.slot_item(i)).collect::<Vec<_>>();
+        let list = List::new(items).highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        self.list.select(Some(self.cursor));
+        frame.render_stateful_widget(list, body, &mut self.list);
+
+        let status = if let Some(comment) = &self.comment {
+            format!("Comment for {}: {}", Slot(*self.selection().start()), comment)
+        } else {
+            self.message.clone()
+        };
+        frame.render_widget(Paragraph::new(status), footer);
+    }
+
+    /// Draw and handle key presses until the editor is closed.
+    pub fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> io::Result<()> {
+        while !self.quit {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    self.handle(key);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Edit the given day, or its plan, in the terminal.
+pub fn run(settings: &Settings, date: NaiveDate, plan: bool) -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    let result = Editor::new(settings, date, plan).run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::backend::TestBackend;
+    use super::*;
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, 24).unwrap()
+    }
+
+    fn press(editor: &mut Editor, code: KeyCode) {
+        editor.handle(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    fn press_shift(editor: &mut Editor, code: KeyCode) {
+        editor.handle(KeyEvent::new(code, KeyModifiers::SHIFT));
+    }
+
+    fn type_text(editor: &mut Editor, text: &str) {
+        for c in text.chars() {
+            press(editor, KeyCode::Char(c));
+        }
+    }
+
+    fn names<'e>(editor: &'e Editor, slots: RangeInclusive<usize>) -> Vec<Option<&'e str>> {
+        slots.map(|i| editor.day.layer(editor.plan)[i].as_ref().map(|a| a.name.as_str())).collect()
+    }
+
+    /// The lines of the screen after drawing the editor.
+    fn screen(editor: &mut Editor) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(100, 8)).unwrap();
+        terminal.draw(|frame| editor.draw(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect::<String>().trim_end().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn moves_the_cursor_within_the_day() {
+        let settings = Settings::with_activities(&["Work", "Learn"]);
+        let mut editor = Editor::new(&settings, date(), false);
+        assert_eq!(editor.cursor, 0);
+        press(&mut editor, KeyCode::Up);
+        assert_eq!(editor.cursor, 0);
+        press(&mut editor, KeyCode::Down);
+        press(&mut editor, KeyCode::Down);
+        assert_eq!(editor.cursor, 2);
+        press(&mut editor, KeyCode::PageDown);
+        assert_eq!(editor.cursor, 2 + SLOTS_PER_HOUR);
+        press(&mut editor, KeyCode::End);
+        assert_eq!(editor.cursor, DAY_SLOTS - 1);
+        press(&mut editor, KeyCode::Down);
+        assert_eq!(editor.cursor, DAY_SLOTS - 1);
+        press(&mut editor, KeyCode::Home);
+        assert_eq!(editor.cursor, 0);
+        assert_eq!(editor.selection(), 0..=0);
+    }
+
+    #[test]
+    fn extends_the_selection_with_shift() {
+        let settings = Settings::with_activities(&["Work", "Learn"]);
+        let mut editor = Editor::new(&settings, date(), false);
+        press(&mut editor, KeyCode::Down);
+        press(&mut editor, KeyCode::Down);
+        press_shift(&mut editor, KeyCode::Down);
+        press_shift(&mut editor, KeyCode::Down);
+        assert_eq!(editor.selection(), 2..=4);
+        // The selection keeps its anchor when moving back past it.
+        press_shift(&mut editor, KeyCode::PageUp);
+        assert_eq!(editor.selection(), 0..=2);
+        press(&mut editor, KeyCode::Down);
+        assert_eq!(editor.selection(), 1..=1);
+    }
+
+    #[test]
+    fn fills_and_clears_the_selection() {
+        let settings = Settings::with_activities(&["Work", "Learn"]);
+        let mut editor = Editor::new(&settings, date(), false);
+        press(&mut editor, KeyCode::Down);
+        press_shift(&mut editor, KeyCode::Down);
+        press_shift(&mut editor, KeyCode::Down);
+        press(&mut editor, KeyCode::Char('w'));
+        assert_eq!(names(&editor, 0..=4), [None, Some("Work"), Some("Work"), Some("Work"), None]);
+        // Filling ends the selection, so only the slot under the cursor is
+        // cleared.
+        assert_eq!(editor.selection(), 3..=3);
+        press(&mut editor, KeyCode::Delete);
+        assert_eq!(names(&editor, 0..=4), [None, Some("Work"), Some("Work"), None, None]);
+        press(&mut editor, KeyCode::Up);
+        press(&mut editor, KeyCode::Char('l'));
+        assert_eq!(names(&editor, 0..=4), [None, Some("Work"), Some("Learn"), None, None]);
+        assert!(editor.day.planned.iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn reports_unknown_shortcuts() {
+        let settings = Settings::with_activities(&["Work", "Learn"]);
+        let mut editor = Editor::new(&settings, date(), false);
+        press(&mut editor, KeyCode::Char('z'));
+        assert!(editor.day.time_slots.iter().all(Option::is_none));
+        assert_eq!(screen(&mut editor).last().unwrap(), "No activity with the shortcut 'z'.");
+    }
+
+    #[test]
+    fn edits_the_comment_of_the_first_selected_slot() {
+        let settings = Settings::with_activities(&["Work", "Learn"]);
+        let mut editor = Editor::new(&settings, date(), false);
+        press_shift(&mut editor, KeyCode::Down);
+        press(&mut editor, KeyCode::Char('w'));
+        press_shift(&mut editor, KeyCode::Up);
+        press(&mut editor, KeyCode::Enter);
+        type_text(&mut editor, "Reviewx");
+        press(&mut editor, KeyCode::Backspace);
+        assert_eq!(screen(&mut editor).last().unwrap(), "Comment for 04:00: Review");
+        press(&mut editor, KeyCode::Enter);
+        assert_eq!(editor.day.time_slots[0].as_ref().unwrap().comment.as_deref(), Some("Review"));
+        assert_eq!(editor.day.time_slots[1].as_ref().unwrap().comment, None);
+        assert!(screen(&mut editor).iter().any(|line| line.ends_with("Work - Review")));
+
+        // Esc cancels, and an empty comment removes it.
+        press(&mut editor, KeyCode::Enter);
+        type_text(&mut editor, " again");
+        press(&mut editor, KeyCode::Esc);
+        assert_eq!(editor.day.time_slots[0].as_ref().unwrap().comment.as_deref(), Some("Review"));
+        assert!(!editor.quit);
+        press(&mut editor, KeyCode::Enter);
+        for _ in 0.."Review".len() {
+            press(&mut editor, KeyCode::Backspace);
+        }
+        press(&mut editor, KeyCode::Enter);
+        assert_eq!(editor.day.time_slots[0].as_ref().unwrap().comment, None);
+    }
+
+    #[test]
+    fn does_not_comment_empty_slots() {
+        let settings = Settings::with_activities(&["Work", "Learn"]);
+        let mut editor = Editor::new(&settings, date(), false);
+        press(&mut editor, KeyCode::Enter);
+        type_text(&mut editor, "Nothing");
+        press(&mut editor, KeyCode::Enter);
+        assert!(editor.day.time_slots[0].is_none());
+        assert_eq!(screen(&mut editor).last().unwrap(), "There is no activity at 04:00.");
+    }
+
+    #[test]
+    fn marks_the_day_as_modified() {
+        let settings = Settings::with_activities(&["Work", "Learn"]);
+        let mut editor = Editor::new(&settings, date(), false);
+        assert_eq!(screen(&mut editor)[0], "Mon 2025-03-24 - Hours Productive: 0");
+        press(&mut editor, KeyCode::Down);
+        assert!(!screen(&mut editor)[0].contains("(modified)"));
+        press_shift(&mut editor, KeyCode::Down);
+        press(&mut editor, KeyCode::Char('w'));
+        assert_eq!(screen(&mut editor)[0], "Mon 2025-03-24 (modified) - Hours Productive: 0.5");
+    }
+
+    #[test]
+    fn edits_the_plan() {
+        let settings = Settings::with_activities(&["Work", "Learn"]);
+        let mut editor = Editor::new(&settings, date(), true);
+        assert_eq!(screen(&mut editor)[0], "Plan for Mon 2025-03-24 - Hours Productive: 0");
+        press(&mut editor, KeyCode::Char('l'));
+        assert_eq!(editor.day.planned[0].as_ref().map(|a| a.name.as_str()), Some("Learn"));
+        assert!(editor.day.time_slots.iter().all(Option::is_none));
+        let screen = screen(&mut editor);
+        assert!(screen[0].starts_with("Plan for Mon 2025-03-24 (modified)"));
+        assert!(screen[1].contains("04:00 ██ Learn"));
+    }
+}